@@ -1,2 +1,3 @@
 pub mod process_service;
+pub mod refresh_worker;
 pub mod rule_service;
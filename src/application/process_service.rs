@@ -1,17 +1,188 @@
-use crate::domain::models::ProcessInfo;
+use crate::domain::models::{
+    AttributeReading, Autogroup, CgroupResources, EnrichedRule, MatchKind, MatchingMode, ProcessAttributes,
+    ProcessInfo, ProcessRecord, ProcessSnapshot, ProcessStatus, ProcessTreeNode, RuleReport, SystemCapabilities,
+};
+use crate::infrastructure::proc_scanner::{ProcEntry, ProcScanner, ScanDelta};
+use crate::infrastructure::process_reader::ProcessReader;
+use crate::infrastructure::system_probe::SystemProbe;
 
-use libc::{SCHED_BATCH, SCHED_FIFO, SCHED_IDLE, SCHED_OTHER, SCHED_RR, SYS_ioprio_get, SYS_sched_getattr, syscall};
 use std::borrow::Cow;
-use std::collections::HashSet;
-use std::fs;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
+
+#[derive(Default)]
+struct ProcessIndex
+{
+    names: HashMap<String, BTreeSet<i32>>,
+    truncated_names: HashMap<String, BTreeSet<i32>>,
+    argv_names: HashMap<String, BTreeMap<i32, usize>>,
+    comm_names: HashMap<i32, String>,
+}
+
+impl ProcessIndex
+{
+    fn insert(&mut self, process_id: i32, process_name: &str, cmdline: &[String])
+    {
+        self.comm_names.insert(process_id, process_name.to_string());
+
+        let name = process_name.to_lowercase();
+        if name.len() == 15
+        {
+            self.truncated_names.entry(name.clone()).or_default().insert(process_id);
+        }
+        self.names.entry(name).or_default().insert(process_id);
+
+        for (index, arg_name) in Self::argv_basenames(cmdline)
+        {
+            self.argv_names
+                .entry(arg_name)
+                .or_default()
+                .entry(process_id)
+                .or_insert(index);
+        }
+    }
+
+    fn remove(&mut self, process_id: i32, process_name: &str, cmdline: &[String])
+    {
+        self.comm_names.remove(&process_id);
+
+        let name = process_name.to_lowercase();
+
+        Self::remove_from(&mut self.truncated_names, &name, process_id);
+        Self::remove_from(&mut self.names, &name, process_id);
+
+        for (_, arg_name) in Self::argv_basenames(cmdline)
+        {
+            if let Some(ids) = self.argv_names.get_mut(&arg_name)
+            {
+                ids.remove(&process_id);
+
+                if ids.is_empty()
+                {
+                    self.argv_names.remove(&arg_name);
+                }
+            }
+        }
+    }
+
+    // Matches are ordered by how trustworthy they are, so callers looking at the first one see the best match.
+    fn lookup(&self, rule_name: &str, matching_mode: MatchingMode) -> Vec<(i32, MatchKind)>
+    {
+        let rule_lower = rule_name.to_lowercase();
+        let mut matches: BTreeMap<i32, MatchKind> = BTreeMap::new();
+
+        // The daemon compares names case-sensitively, the index itself is case-insensitive.
+        let comm_name_matches = |process_id: i32, match_kind: MatchKind| {
+            let comm_name = self.comm_names.get(&process_id).map(String::as_str).unwrap_or("");
+
+            match (matching_mode, match_kind)
+            {
+                (MatchingMode::Discovery, _) => true,
+                (_, MatchKind::Truncated) => rule_name.get(..15) == Some(comm_name),
+                _ => comm_name == rule_name,
+            }
+        };
+
+        let mut record = |process_id: i32, match_kind: MatchKind| {
+            if !matching_mode.allows(match_kind) || !comm_name_matches(process_id, match_kind)
+            {
+                return;
+            }
+
+            let best_kind = matches.entry(process_id).or_insert(match_kind);
+
+            if match_kind < *best_kind
+            {
+                *best_kind = match_kind;
+            }
+        };
+
+        let mut exe_variants = vec![format!("{}.exe", rule_lower)];
+
+        if let Some(stripped) = rule_lower.strip_suffix(".exe")
+        {
+            exe_variants.push(stripped.to_string());
+        }
+
+        for process_id in self.names.get(&rule_lower).into_iter().flatten()
+        {
+            record(*process_id, MatchKind::Comm);
+        }
+
+        for exe_variant in &exe_variants
+        {
+            for process_id in self.names.get(exe_variant).into_iter().flatten()
+            {
+                record(*process_id, MatchKind::ExeSuffix);
+            }
+        }
+
+        if rule_lower.len() > 15
+            && let Some(truncated) = rule_lower.get(..15)
+        {
+            for process_id in self.truncated_names.get(truncated).into_iter().flatten()
+            {
+                record(*process_id, MatchKind::Truncated);
+            }
+        }
+
+        for arg_name in std::iter::once(&rule_lower).chain(&exe_variants)
+        {
+            for (process_id, index) in self.argv_names.get(arg_name).into_iter().flatten()
+            {
+                record(*process_id, MatchKind::ArgvBasename { index: *index });
+            }
+        }
+
+        let mut matches: Vec<(i32, MatchKind)> = matches.into_iter().collect();
+        matches.sort_by_key(|(process_id, match_kind)| (*match_kind, *process_id));
+        matches
+    }
+
+    fn argv_basenames(cmdline: &[String]) -> impl Iterator<Item = (usize, String)> + '_
+    {
+        cmdline.iter().enumerate().filter_map(|(index, arg)| {
+            Path::new(arg)
+                .file_name()
+                .map(|file_name| (index, file_name.to_string_lossy().to_lowercase()))
+        })
+    }
+
+    fn remove_from(names: &mut HashMap<String, BTreeSet<i32>>, name: &str, process_id: i32)
+    {
+        if let Some(ids) = names.get_mut(name)
+        {
+            ids.remove(&process_id);
+
+            if ids.is_empty()
+            {
+                names.remove(name);
+            }
+        }
+    }
+}
 
 pub struct ProcessService
 {
-    system: System,
-    process_names: HashSet<String>,
-    truncated_process_names: HashSet<String>,
+    scanner: Option<ProcScanner>,
+    snapshot: Option<ProcessSnapshot>,
+    capabilities: Option<SystemCapabilities>,
+    processes: HashMap<i32, ProcEntry>,
+    process_index: ProcessIndex,
+    process_info_cache: RefCell<HashMap<i32, ProcessInfo>>,
+    // Attributes read along with the last scan, in which case nothing is read on demand.
+    process_attributes: Option<ProcessAttributes>,
+    process_reader: ProcessReader,
+    matching_mode: MatchingMode,
+}
+
+impl Default for ProcessService
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
 }
 
 impl ProcessService
@@ -19,136 +190,362 @@ impl ProcessService
     pub fn new() -> Self
     {
         let mut process_service = Self {
-            system: System::new_with_specifics(RefreshKind::nothing().with_processes(ProcessRefreshKind::everything())),
-            process_names: HashSet::new(),
-            truncated_process_names: HashSet::new(),
+            scanner: Some(ProcScanner::new()),
+            snapshot: None,
+            capabilities: None,
+            processes: HashMap::new(),
+            process_index: ProcessIndex::default(),
+            process_info_cache: RefCell::new(HashMap::new()),
+            process_attributes: None,
+            process_reader: ProcessReader::new(),
+            matching_mode: MatchingMode::default(),
         };
 
+        let (_, _, own_latency_nice) = process_service
+            .process_reader
+            .read_scheduler_info(std::process::id() as i32);
+        process_service.capabilities = Some(SystemProbe::new().probe(own_latency_nice.is_value()));
+
         process_service.update_processes();
         process_service
     }
 
-    pub fn get_process_infos(&self, rule_name: &str) -> Vec<ProcessInfo>
+    pub fn from_snapshot(snapshot: ProcessSnapshot) -> Self
     {
-        let mut process_infos = Vec::new();
+        let mut process_index = ProcessIndex::default();
+        let mut process_info_cache = HashMap::new();
 
-        for (pid, process) in self.system.processes()
+        for record in &snapshot.processes
         {
-            let process_name = process.name().to_string_lossy();
+            process_index.insert(record.info.process_id, &record.info.name, &record.cmdline);
+            process_info_cache.insert(record.info.process_id, record.info.clone());
+        }
 
-            if self.matches_rule_name(rule_name, process)
-            {
-                let pid_int = pid.as_u32() as i32;
-
-                let nice = self.read_nice(pid_int);
-                let oom_score_adj = self.read_oom_score(pid_int);
-                let cgroup = self.read_cgroup(pid_int);
-                let (sched_policy, rtprio, latency_nice) = self.read_scheduler_info(pid_int);
-                let ioclass = self.read_io_priority(pid_int);
-
-                process_infos.push(ProcessInfo {
-                    process_id: pid_int,
-                    name: process_name.to_string(),
-                    nice,
-                    oom_score_adj,
-                    cgroup,
-                    sched_policy,
-                    rtprio,
-                    ioclass,
-                    latency_nice,
-                });
-            }
+        Self {
+            scanner: None,
+            capabilities: snapshot.capabilities.clone(),
+            snapshot: Some(snapshot),
+            processes: HashMap::new(),
+            process_index,
+            process_info_cache: RefCell::new(process_info_cache),
+            process_attributes: None,
+            process_reader: ProcessReader::new(),
+            matching_mode: MatchingMode::default(),
         }
+    }
 
-        process_infos
+    pub fn snapshot_captured_at(&self) -> Option<&str>
+    {
+        self.snapshot.as_ref().map(|snapshot| snapshot.captured_at.as_str())
     }
 
-    pub fn is_process_active(&self, rule_name: &str) -> bool
+    pub fn capabilities(&self) -> Option<&SystemCapabilities>
     {
-        let rule_lower = rule_name.to_lowercase();
+        self.capabilities.as_ref()
+    }
+
+    pub fn matching_mode(&self) -> MatchingMode
+    {
+        self.matching_mode
+    }
+
+    pub fn set_matching_mode(&mut self, matching_mode: MatchingMode)
+    {
+        self.matching_mode = matching_mode;
+    }
 
-        if self.process_names.contains(&rule_lower)
+    pub fn take_scanner(&mut self) -> Option<ProcScanner>
+    {
+        self.scanner.take()
+    }
+
+    pub fn capture_snapshot(&self) -> ProcessSnapshot
+    {
+        if let Some(snapshot) = &self.snapshot
         {
-            return true;
+            return snapshot.clone();
         }
 
-        if let Some(stripped) = rule_lower.strip_suffix(".exe")
+        let mut processes: Vec<ProcessRecord> = self
+            .processes
+            .iter()
+            .map(|(process_id, entry)| ProcessRecord {
+                info: self.process_reader.read_process_info(*process_id, &entry.name),
+                cmdline: entry.cmdline.clone(),
+                exe: self.process_reader.read_exe(*process_id),
+                uid: self.process_reader.read_uid(*process_id),
+                parent_process_id: Some(entry.parent_process_id),
+            })
+            .collect();
+
+        processes.sort_by_key(|record| record.info.process_id);
+
+        ProcessSnapshot {
+            captured_at: chrono::Local::now().to_rfc3339(),
+            hostname: self.process_reader.read_kernel_value("hostname"),
+            kernel_version: self.process_reader.read_kernel_value("osrelease"),
+            capabilities: self.capabilities.clone(),
+            processes,
+        }
+    }
+
+    pub fn get_process_infos(&self, rule_name: &str) -> Vec<ProcessInfo>
+    {
+        self.process_index
+            .lookup(rule_name, self.matching_mode)
+            .into_iter()
+            .filter_map(|(process_id, _)| self.get_process_info(process_id))
+            .collect()
+    }
+
+    pub fn get_process_info(&self, process_id: i32) -> Option<ProcessInfo>
+    {
+        if let Some(process_attributes) = &self.process_attributes
         {
-            if self.process_names.contains(stripped)
-            {
-                return true;
-            }
+            return process_attributes.infos.get(&process_id).cloned();
         }
 
-        let with_exe = format!("{}.exe", rule_lower);
-        if self.process_names.contains(&with_exe)
+        let mut process_info_cache = self.process_info_cache.borrow_mut();
+
+        if let Some(process_info) = process_info_cache.get(&process_id)
         {
-            return true;
+            return Some(process_info.clone());
         }
 
-        if rule_lower.len() > 15
+        let process_info = self
+            .process_reader
+            .read_process_info(process_id, &self.processes.get(&process_id)?.name);
+        process_info_cache.insert(process_id, process_info.clone());
+
+        Some(process_info)
+    }
+
+    // Maps every matched process to the best matching rule. Shadowed rules never apply, so they are skipped.
+    pub fn match_rules<'a>(&self, rules: &'a [EnrichedRule]) -> HashMap<i32, (&'a EnrichedRule, MatchKind)>
+    {
+        let mut rule_matches: HashMap<i32, (&'a EnrichedRule, MatchKind)> = HashMap::new();
+
+        for rule in rules.iter().filter(|rule| !rule.shadowed)
         {
-            let truncated = &rule_lower[..15];
-            if self.truncated_process_names.contains(truncated)
+            let Some(rule_name) = rule.data.name.as_deref()
+            else
             {
-                return true;
+                continue;
+            };
+
+            for (process_id, match_kind) in self.process_index.lookup(rule_name, self.matching_mode)
+            {
+                let best_match = rule_matches.entry(process_id).or_insert((rule, match_kind));
+
+                if match_kind < best_match.1
+                {
+                    *best_match = (rule, match_kind);
+                }
             }
         }
 
-        false
+        rule_matches
     }
 
-    fn matches_rule_name(&self, rule_name: &str, process: &sysinfo::Process) -> bool
+    pub fn process_tree(&self) -> Vec<ProcessTreeNode>
     {
-        let proc_name = process.name().to_string_lossy();
-        if self.check_name_match(rule_name, &proc_name)
+        let nodes: Vec<ProcessTreeNode> = match &self.snapshot
+        {
+            Some(snapshot) => snapshot
+                .processes
+                .iter()
+                .map(|record| ProcessTreeNode {
+                    process_id: record.info.process_id,
+                    parent_process_id: record.parent_process_id,
+                    name: record.info.name.clone(),
+                    depth: 0,
+                })
+                .collect(),
+            None => self
+                .processes
+                .iter()
+                .map(|(process_id, entry)| ProcessTreeNode {
+                    process_id: *process_id,
+                    parent_process_id: Some(entry.parent_process_id),
+                    name: entry.name.clone(),
+                    depth: 0,
+                })
+                .collect(),
+        };
+
+        let known_process_ids: HashSet<i32> = nodes.iter().map(|node| node.process_id).collect();
+        let mut children: HashMap<Option<i32>, Vec<ProcessTreeNode>> = HashMap::new();
+
+        for node in nodes
         {
-            return true;
+            // Anything whose parent is not visible to us (pid 0, another namespace) becomes a root.
+            let parent_key = node
+                .parent_process_id
+                .filter(|parent_id| known_process_ids.contains(parent_id));
+
+            children.entry(parent_key).or_default().push(node);
         }
 
-        for arg in process.cmd()
+        for siblings in children.values_mut()
         {
-            let path = Path::new(arg);
-            if let Some(file_name) = path.file_name()
+            siblings.sort_by_key(|node| std::cmp::Reverse(node.process_id));
+        }
+
+        let mut tree = Vec::new();
+        let mut stack = children.remove(&None).unwrap_or_default();
+
+        while let Some(node) = stack.pop()
+        {
+            if let Some(mut child_nodes) = children.remove(&Some(node.process_id))
             {
-                let name = file_name.to_string_lossy();
-                if self.check_name_match(rule_name, &name)
+                for child_node in &mut child_nodes
                 {
-                    return true;
+                    child_node.depth = node.depth + 1;
                 }
+
+                stack.extend(child_nodes);
             }
+
+            tree.push(node);
         }
 
-        false
+        tree
     }
 
-    fn check_name_match(&self, rule_name: &str, proc_name: &str) -> bool
+    // With autogroup enabled the scheduler first balances CPU time between autogroups, so a per-process nice
+    // only matters against processes of the same session unless the autogroup nice agrees with it. A process
+    // alone in an autogroup of nice 0 competes like an ungrouped process, so that case is left alone.
+    pub fn autogroup_overrides_nice(&self, rule_nice: Option<i32>, process_info: &ProcessInfo) -> bool
     {
-        if rule_name.eq_ignore_ascii_case(proc_name)
+        let autogroup_enabled = self
+            .capabilities
+            .as_ref()
+            .is_none_or(|capabilities| capabilities.autogroup_enabled == Some(true));
+
+        match (rule_nice, process_info.autogroup.value())
         {
-            return true;
+            (Some(nice), Some(autogroup)) =>
+            {
+                autogroup_enabled
+                    && autogroup.nice != nice
+                    && (autogroup.nice != 0 || self.shares_autogroup(process_info.process_id, autogroup))
+            }
+            _ => false,
         }
+    }
 
-        if proc_name.len() > 4
-            && proc_name[proc_name.len() - 4..].eq_ignore_ascii_case(".exe")
-            && proc_name[..proc_name.len() - 4].eq_ignore_ascii_case(rule_name)
+    // Snapshots know every autogroup, live processes are compared by session, which autogroups follow.
+    fn shares_autogroup(&self, process_id: i32, autogroup: &Autogroup) -> bool
+    {
+        match &self.snapshot
         {
-            return true;
+            Some(snapshot) => snapshot.processes.iter().any(|record| {
+                record.info.process_id != process_id
+                    && record
+                        .info
+                        .autogroup
+                        .value()
+                        .is_some_and(|other| other.id == autogroup.id)
+            }),
+            None => self.processes.get(&process_id).is_some_and(|entry| {
+                self.processes
+                    .iter()
+                    .any(|(other_id, other)| *other_id != process_id && other.session_id == entry.session_id)
+            }),
         }
+    }
 
-        if rule_name.len() > 4
-            && rule_name[rule_name.len() - 4..].eq_ignore_ascii_case(".exe")
-            && rule_name[..rule_name.len() - 4].eq_ignore_ascii_case(proc_name)
+    pub fn get_cgroup_resources(&self, process_info: &ProcessInfo) -> Option<CgroupResources>
+    {
+        // Limits are read live from the cgroup2 mount, a snapshot only knows the path.
+        if self.snapshot.is_some()
         {
-            return true;
+            return None;
         }
 
-        if proc_name.len() == 15 && rule_name.len() > 15 && rule_name[..15].eq_ignore_ascii_case(proc_name)
+        if let Some(process_attributes) = &self.process_attributes
         {
-            return true;
+            return process_attributes
+                .cgroup_resources
+                .get(&process_info.process_id)
+                .cloned();
+        }
+
+        let cgroup2_mount = self.capabilities.as_ref()?.cgroup2_mount.as_deref()?;
+        let cgroup_path = self.process_reader.read_unified_cgroup(process_info.process_id)?;
+
+        Some(self.process_reader.read_cgroup_resources(&cgroup_path, cgroup2_mount))
+    }
+
+    pub fn explain_match(&self, rule_name: &str, process_id: i32) -> Option<MatchKind>
+    {
+        self.process_index
+            .lookup(rule_name, self.matching_mode)
+            .into_iter()
+            .find(|(matched_process_id, _)| *matched_process_id == process_id)
+            .map(|(_, match_kind)| match_kind)
+    }
+
+    pub fn rule_report(&self, rule: &EnrichedRule) -> RuleReport
+    {
+        let rule_name = rule.data.name.as_deref().unwrap_or_default();
+        let processes = self
+            .process_index
+            .lookup(rule_name, self.matching_mode)
+            .into_iter()
+            .filter_map(|(process_id, match_kind)| {
+                let info = self.get_process_info(process_id)?;
+                let mismatches = Self::find_mismatches(rule, &info);
+
+                Some(ProcessStatus {
+                    info,
+                    matched_by: match_kind,
+                    mismatches,
+                })
+            })
+            .collect();
+
+        RuleReport {
+            rule: rule.data.clone(),
+            category: rule.category().to_string(),
+            source_file: rule.source_file.clone(),
+            shadowed: rule.shadowed,
+            context_comment: rule.context_comment.clone(),
+            processes,
         }
+    }
+
+    // Unreadable values are not mismatches, the reading itself tells why.
+    fn find_mismatches(rule: &EnrichedRule, process_info: &ProcessInfo) -> Vec<String>
+    {
+        let differs_i32 = |want: Option<i32>, have: &AttributeReading<i32>| matches!((want, have), (Some(wanted), AttributeReading::Value(had)) if wanted != *had);
+        let differs_str = |want: &Option<String>, have: &AttributeReading<String>| matches!((want, have), (Some(wanted), AttributeReading::Value(had)) if !wanted.eq_ignore_ascii_case(had));
+
+        let checks = [
+            ("nice", differs_i32(rule.data.nice, &process_info.nice)),
+            (
+                "latency_nice",
+                differs_i32(rule.data.latency_nice, &process_info.latency_nice),
+            ),
+            ("sched", differs_str(&rule.data.sched, &process_info.sched_policy)),
+            ("ioclass", differs_str(&rule.data.ioclass, &process_info.ioclass)),
+            (
+                "oom_score_adj",
+                differs_i32(rule.data.oom_score_adj, &process_info.oom_score_adj),
+            ),
+            ("cgroup", differs_str(&rule.data.cgroup, &process_info.cgroup)),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(_, differs)| *differs)
+            .map(|(field, _)| field.to_string())
+            .collect()
+    }
 
-        false
+    pub fn is_process_active(&self, rule_name: &str) -> bool
+    {
+        !self.process_index.lookup(rule_name, self.matching_mode).is_empty()
     }
 
     pub fn shorten_cgroup(cgroup_path: &str) -> Cow<'_, str>
@@ -174,180 +571,59 @@ impl ProcessService
     {
         let query_lower = query.to_lowercase();
         let mut results: Vec<String> = self
-            .process_names
-            .iter()
+            .process_index
+            .names
+            .keys()
+            .chain(self.process_index.argv_names.keys())
             .filter(|name| name.contains(&query_lower))
             .cloned()
             .collect();
         results.sort();
+        results.dedup();
         results
     }
 
     pub fn update_processes(&mut self)
     {
-        self.system.refresh_processes(ProcessesToUpdate::All, true);
-
-        self.process_names.clear();
-        self.truncated_process_names.clear();
-
-        for process in self.system.processes().values()
+        if let Some(scanner) = &mut self.scanner
         {
-            let name = process.name().to_string_lossy().to_lowercase();
-            if name.len() == 15
-            {
-                self.truncated_process_names.insert(name.clone());
-            }
-            self.process_names.insert(name);
-
-            for arg in process.cmd()
-            {
-                let path = Path::new(arg);
-                if let Some(file_name) = path.file_name()
-                {
-                    let name = file_name.to_string_lossy().to_lowercase();
-                    self.process_names.insert(name);
-                }
-            }
+            let scan_delta = scanner.scan();
+            self.apply_scan(scan_delta);
         }
     }
 
-    fn read_nice(&self, pid: i32) -> Option<i32>
+    pub fn apply_scan(&mut self, scan_delta: ScanDelta)
     {
-        unsafe {
-            let val = libc::getpriority(0, pid as u32);
-
-            Some(val)
-        }
-    }
-
-    fn read_oom_score(&self, pid: i32) -> Option<i32>
-    {
-        let oom_score_path = format!("/proc/{}/oom_score_adj", pid);
-
-        fs::read_to_string(oom_score_path)
-            .ok()
-            .and_then(|content| content.trim().parse().ok())
-    }
-
-    fn read_cgroup(&self, pid: i32) -> Option<String>
-    {
-        let cgroup_path = format!("/proc/{}/cgroup", pid);
-        let cgroup_content = fs::read_to_string(cgroup_path).ok()?;
-
-        for cgroup_line in cgroup_content.lines()
+        for (process_id, entry) in scan_delta.removed
         {
-            let cgroup_line_parts: Vec<&str> = cgroup_line.split(':').collect();
+            self.process_index.remove(process_id, &entry.name, &entry.cmdline);
+            self.processes.remove(&process_id);
 
-            if cgroup_line_parts.len() == 3
+            if let Some(process_attributes) = &mut self.process_attributes
             {
-                let cgroup_path = cgroup_line_parts[2];
-                if cgroup_path != "/" && !cgroup_path.is_empty()
-                {
-                    return Some(cgroup_path.to_string());
-                }
+                process_attributes.infos.remove(&process_id);
+                process_attributes.cgroup_resources.remove(&process_id);
             }
         }
 
-        Some("/".to_string())
-    }
-
-    fn read_scheduler_info(&self, process_id: i32) -> (Option<String>, Option<i32>, Option<i32>)
-    {
-        unsafe {
-            let policy_result = libc::sched_getscheduler(process_id);
-            let policy = if policy_result >= 0
-            {
-                match policy_result
-                {
-                    SCHED_OTHER => Some("normal".to_string()),
-                    SCHED_FIFO => Some("fifo".to_string()),
-                    SCHED_RR => Some("rr".to_string()),
-                    SCHED_BATCH => Some("batch".to_string()),
-                    SCHED_IDLE => Some("idle".to_string()),
-                    6 => Some("deadline".to_string()),
-                    _ => Some(format!("unknown({})", policy_result)),
-                }
-            }
-            else
-            {
-                None
-            };
-
-            let mut sched_priority_param: libc::sched_param = std::mem::zeroed();
-            let rtprio = if libc::sched_getparam(process_id, &mut sched_priority_param) == 0
-            {
-                Some(sched_priority_param.sched_priority)
-            }
-            else
-            {
-                None
-            };
-
-            #[repr(C)]
-            struct SchedAttr
-            {
-                size: u32,
-                sched_policy: u32,
-                sched_flags: u64,
-                sched_nice: i32,
-                sched_priority: u32,
-                sched_runtime: u64,
-                sched_deadline: u64,
-                sched_period: u64,
-                sched_util_min: u32,
-                sched_util_max: u32,
-                sched_latency_nice: i32,
-            }
-
-            let mut latency_nice_attribute: SchedAttr = std::mem::zeroed();
-            latency_nice_attribute.size = std::mem::size_of::<SchedAttr>() as u32;
-
-            let result = syscall(
-                SYS_sched_getattr,
-                process_id,
-                &mut latency_nice_attribute as *mut SchedAttr,
-                latency_nice_attribute.size,
-                0,
-            );
-
-            let latency_nice = if result == 0
-            {
-                Some(latency_nice_attribute.sched_latency_nice)
-            }
-            else
-            {
-                None
-            };
-
-            (policy, rtprio, latency_nice)
+        for (process_id, entry) in scan_delta.added
+        {
+            self.process_index.insert(process_id, &entry.name, &entry.cmdline);
+            self.processes.insert(process_id, entry);
         }
+
+        self.process_info_cache.get_mut().clear();
     }
 
-    fn read_io_priority(&self, process_id: i32) -> Option<String>
+    // Readings from a refresh worker, added to the earlier ones. Once any arrived, processes without readings
+    // show none instead of being read on demand.
+    pub fn apply_attributes(&mut self, process_attributes: ProcessAttributes)
     {
-        unsafe {
-            let io_priority = syscall(SYS_ioprio_get, 1, process_id);
-
-            if io_priority >= 0
-            {
-                let io_priority_value = io_priority as i32;
-                let ioclass_id = io_priority_value >> 13;
+        let known_attributes = self.process_attributes.get_or_insert_default();
 
-                let ioclass = match ioclass_id
-                {
-                    0 => Some("none".to_string()),
-                    1 => Some("realtime".to_string()),
-                    2 => Some("best-effort".to_string()),
-                    3 => Some("idle".to_string()),
-                    _ => Some(format!("unknown({})", ioclass_id)),
-                };
-
-                ioclass
-            }
-            else
-            {
-                None
-            }
-        }
+        known_attributes.infos.extend(process_attributes.infos);
+        known_attributes
+            .cgroup_resources
+            .extend(process_attributes.cgroup_resources);
     }
 }
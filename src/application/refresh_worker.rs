@@ -0,0 +1,268 @@
+use crate::application::rule_service::RuleService;
+use crate::domain::models::{
+    CgroupDefinition, CgroupResources, Diagnostic, EnrichedRule, ProcessAttributes, TypeDefinition,
+};
+use crate::infrastructure::proc_scanner::{ProcScanner, ScanDelta};
+use crate::infrastructure::process_reader::ProcessReader;
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{Scope, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+pub enum WorkerCommand
+{
+    ReloadRules,
+    // The processes whose attributes the UI shows, any others are never read.
+    WatchProcesses(HashSet<i32>),
+    Shutdown,
+}
+
+pub enum WorkerEvent
+{
+    ScanStarted,
+    ProcessesScanned(ScanDelta),
+    // Readings of watched processes that were just started, replaced or watched, sent after their scan.
+    AttributesRead(ProcessAttributes),
+    RulesLoaded(Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>),
+    CgroupsLoaded(Result<(Vec<CgroupDefinition>, Vec<Diagnostic>)>),
+    TypesLoaded(Result<(Vec<TypeDefinition>, Vec<Diagnostic>)>),
+}
+
+pub struct RefreshWorker<'scope>
+{
+    command_sender: Sender<WorkerCommand>,
+    event_receiver: Receiver<WorkerEvent>,
+    handle: ScopedJoinHandle<'scope, ()>,
+}
+
+impl<'scope> RefreshWorker<'scope>
+{
+    pub fn spawn<'env>(
+        scope: &'scope Scope<'scope, 'env>,
+        rule_service: &'env RuleService,
+        scanner: Option<ProcScanner>,
+        cgroup2_mount: Option<PathBuf>,
+        scan_interval: Duration,
+    ) -> Self
+    {
+        let (command_sender, command_receiver) = mpsc::channel();
+        let (event_sender, event_receiver) = mpsc::channel();
+
+        let handle = scope.spawn(move || {
+            Self::run(
+                rule_service,
+                scanner,
+                cgroup2_mount,
+                scan_interval,
+                command_receiver,
+                event_sender,
+            );
+        });
+
+        Self {
+            command_sender,
+            event_receiver,
+            handle,
+        }
+    }
+
+    pub fn reload_rules(&self)
+    {
+        let _ = self.command_sender.send(WorkerCommand::ReloadRules);
+    }
+
+    pub fn watch_processes(&self, process_ids: HashSet<i32>)
+    {
+        let _ = self.command_sender.send(WorkerCommand::WatchProcesses(process_ids));
+    }
+
+    pub fn try_recv(&self) -> Option<WorkerEvent>
+    {
+        self.event_receiver.try_recv().ok()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WorkerEvent>
+    {
+        self.event_receiver.recv_timeout(timeout).ok()
+    }
+
+    pub fn shutdown(self)
+    {
+        let _ = self.command_sender.send(WorkerCommand::Shutdown);
+        let _ = self.handle.join();
+    }
+
+    fn run(
+        rule_service: &RuleService,
+        mut scanner: Option<ProcScanner>,
+        cgroup2_mount: Option<PathBuf>,
+        scan_interval: Duration,
+        command_receiver: Receiver<WorkerCommand>,
+        event_sender: Sender<WorkerEvent>,
+    )
+    {
+        let process_reader = ProcessReader::new();
+
+        // Watched processes are read once when they show up, later scans keep those readings.
+        let mut watched_processes: HashSet<i32> = HashSet::new();
+        let mut read_processes: HashSet<i32> = HashSet::new();
+
+        // The first scan runs right away, so the UI has the processes it asks attributes for.
+        let mut last_scan: Option<Instant> = None;
+
+        loop
+        {
+            let timeout = last_scan
+                .and_then(|last_scan| scan_interval.checked_sub(last_scan.elapsed()))
+                .unwrap_or_else(|| Duration::from_secs(0));
+
+            match command_receiver.recv_timeout(timeout)
+            {
+                Ok(WorkerCommand::ReloadRules) =>
+                {
+                    let rules_result = rule_service.load_rules();
+
+                    if event_sender.send(WorkerEvent::RulesLoaded(rules_result)).is_err()
+                    {
+                        return;
+                    }
+
+                    let cgroups_result = rule_service.load_cgroup_definitions();
+
+                    if event_sender.send(WorkerEvent::CgroupsLoaded(cgroups_result)).is_err()
+                    {
+                        return;
+                    }
+
+                    let types_result = rule_service.load_type_definitions();
+
+                    if event_sender.send(WorkerEvent::TypesLoaded(types_result)).is_err()
+                    {
+                        return;
+                    }
+                }
+
+                Ok(WorkerCommand::WatchProcesses(process_ids)) =>
+                {
+                    watched_processes = process_ids;
+                    read_processes.retain(|process_id| watched_processes.contains(process_id));
+
+                    if let Some(scanner) = scanner.as_ref()
+                    {
+                        let unread: Vec<i32> = watched_processes
+                            .iter()
+                            .copied()
+                            .filter(|process_id| !read_processes.contains(process_id))
+                            .collect();
+
+                        let process_attributes =
+                            Self::read_attributes(scanner, &process_reader, cgroup2_mount.as_deref(), &unread);
+                        read_processes.extend(process_attributes.infos.keys());
+
+                        if !process_attributes.infos.is_empty()
+                            && event_sender
+                                .send(WorkerEvent::AttributesRead(process_attributes))
+                                .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+
+                Ok(WorkerCommand::Shutdown) | Err(RecvTimeoutError::Disconnected) => return,
+
+                Err(RecvTimeoutError::Timeout) =>
+                {}
+            }
+
+            if last_scan.is_none_or(|last_scan| last_scan.elapsed() >= scan_interval)
+            {
+                if let Some(scanner) = scanner.as_mut()
+                {
+                    if event_sender.send(WorkerEvent::ScanStarted).is_err()
+                    {
+                        return;
+                    }
+
+                    let scan_delta = scanner.scan();
+
+                    for (process_id, _) in &scan_delta.removed
+                    {
+                        read_processes.remove(process_id);
+                    }
+
+                    let started: Vec<i32> = scan_delta
+                        .added
+                        .iter()
+                        .map(|(process_id, _)| *process_id)
+                        .filter(|process_id| watched_processes.contains(process_id))
+                        .collect();
+
+                    if event_sender.send(WorkerEvent::ProcessesScanned(scan_delta)).is_err()
+                    {
+                        return;
+                    }
+
+                    if !started.is_empty()
+                    {
+                        let process_attributes =
+                            Self::read_attributes(scanner, &process_reader, cgroup2_mount.as_deref(), &started);
+                        read_processes.extend(process_attributes.infos.keys());
+
+                        if event_sender
+                            .send(WorkerEvent::AttributesRead(process_attributes))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+
+                last_scan = Some(Instant::now());
+            }
+        }
+    }
+
+    fn read_attributes(
+        scanner: &ProcScanner,
+        process_reader: &ProcessReader,
+        cgroup2_mount: Option<&Path>,
+        process_ids: &[i32],
+    ) -> ProcessAttributes
+    {
+        let mut process_attributes = ProcessAttributes::default();
+
+        // Processes share cgroups, so each cgroup is only read once per batch.
+        let mut cgroup_resources: HashMap<String, CgroupResources> = HashMap::new();
+
+        for &process_id in process_ids
+        {
+            let Some(entry) = scanner.get(process_id)
+            else
+            {
+                continue;
+            };
+
+            process_attributes
+                .infos
+                .insert(process_id, process_reader.read_process_info(process_id, &entry.name));
+
+            if let Some(cgroup2_mount) = cgroup2_mount
+                && let Some(cgroup_path) = process_reader.read_unified_cgroup(process_id)
+            {
+                let resources = cgroup_resources
+                    .entry(cgroup_path)
+                    .or_insert_with_key(|cgroup_path| process_reader.read_cgroup_resources(cgroup_path, cgroup2_mount));
+
+                process_attributes
+                    .cgroup_resources
+                    .insert(process_id, resources.clone());
+            }
+        }
+
+        process_attributes
+    }
+}
@@ -1,7 +1,14 @@
-use crate::domain::models::{AnanicyRule, EnrichedRule};
+use crate::domain::models::{
+    AnanicyRule, BulkAction, CgroupDefinition, Diagnostic, EnrichedRule, FileChange, RuleColumn, RuleLineEdit,
+    RuleSort, RuleStatistics, RuleTreeNode, RuleTreeNodeKind, SavePlan, TypeDefinition,
+};
+use crate::domain::query::{Query, SearchMode};
 use crate::infrastructure::rule_repository::RuleRepository;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::slice;
 
 pub struct RuleService
 {
@@ -15,46 +22,537 @@ impl RuleService
         Self { rule_repository }
     }
 
-    pub fn save_rule(&self, rule: &AnanicyRule) -> Result<()>
+    pub fn save_rule(&self, rule: &AnanicyRule, target_file: Option<&Path>) -> Result<PathBuf>
     {
-        self.rule_repository.save_rule(rule)
+        let save_plan = self.plan_save_rule(rule, target_file)?;
+        self.apply_save_plan(&save_plan)?;
+
+        // Saving a single rule always writes exactly one file.
+        Ok(save_plan.changes[0].file.clone())
     }
 
-    pub fn search_rules(&self, query: &str) -> Result<(Vec<EnrichedRule>, Vec<String>)>
+    // Without a target file the rule gets a file of its own in the custom rules directory.
+    pub fn plan_save_rule(&self, rule: &AnanicyRule, target_file: Option<&Path>) -> Result<SavePlan>
     {
-        let (mut rules, errors) = self.rule_repository.load_all()?;
-        self.mark_shadowed_rules(&mut rules);
+        rule.validate()?;
 
-        let query_lower = query.to_lowercase();
+        let (rules, _) = self.load_rules()?;
 
-        if !query.is_empty()
+        let change = match target_file
         {
-            rules.retain(|r| {
-                let match_str =
-                    |opt: &Option<String>| opt.as_deref().unwrap_or("").to_lowercase().contains(&query_lower);
+            None => self.rule_repository.plan_save_rule(rule)?,
+            Some(target_file) =>
+            {
+                let target_file = self.resolve_target_file(target_file)?;
+
+                // Saving a rule the target already defines replaces that line instead of adding a second one. Within
+                // a file the last definition of a name is the one in effect, so that is the one that gets replaced.
+                let existing_rule = rules.iter().rfind(|existing_rule| {
+                    existing_rule.source_file == target_file && existing_rule.data.name == rule.name
+                });
+
+                match existing_rule
+                {
+                    Some(existing_rule) => self
+                        .rule_repository
+                        .plan_edit_rule_lines(&target_file, &[(existing_rule, RuleLineEdit::Replace(rule.clone()))])?,
+                    None => self.rule_repository.plan_append_rule(&target_file, rule)?,
+                }
+            }
+        };
+
+        self.plan_changes(&rules, vec![change])
+    }
+
+    // Rewrites the line the original rule was loaded from, so a rename replaces it instead of adding a second rule.
+    // A different target file moves the rule there. Without a target file the rule stays in its file.
+    pub fn plan_edit_rule(
+        &self,
+        original: &EnrichedRule,
+        rule: &AnanicyRule,
+        target_file: Option<&Path>,
+    ) -> Result<SavePlan>
+    {
+        rule.validate()?;
 
-                let match_num =
-                    |opt: &Option<i32>| opt.map(|n| n.to_string()).unwrap_or_default().contains(&query_lower);
+        let (rules, _) = self.load_rules()?;
+
+        let target_file = match target_file
+        {
+            Some(target_file) => self.resolve_target_file(target_file)?,
+            None => original.source_file.clone(),
+        };
+
+        // Duplicates the file already held are left alone, only a rename or a move can add one.
+        let renamed_or_moved = rule.name != original.data.name || target_file != original.source_file;
+
+        if renamed_or_moved
+            && let Some(rule_name) = rule.name.as_deref()
+            && rules.iter().any(|existing_rule| {
+                existing_rule.source_file == target_file
+                    && existing_rule.data.name.as_deref() == Some(rule_name)
+                    && !(existing_rule.source_file == original.source_file
+                        && existing_rule.line_number == original.line_number)
+            })
+        {
+            bail!("{:?} already defines a rule named {:?}", target_file, rule_name);
+        }
+
+        let changes = if target_file == original.source_file
+        {
+            vec![
+                self.rule_repository
+                    .plan_edit_rule_lines(&target_file, &[(original, RuleLineEdit::Replace(rule.clone()))])?,
+            ]
+        }
+        else
+        {
+            // Append first, so a failure never loses the rule.
+            vec![
+                self.rule_repository
+                    .plan_append_rules(&target_file, &[(rule, original.context_comment.as_deref())])?,
+                self.rule_repository
+                    .plan_edit_rule_lines(&original.source_file, &[(original, RuleLineEdit::Remove)])?,
+            ]
+        };
+
+        self.plan_changes(&rules, changes)
+    }
 
-                match_str(&r.data.name)
-                    || match_str(&r.data.rule_type)
-                    || match_str(&r.data.sched)
-                    || match_str(&r.data.ioclass)
-                    || match_str(&r.data.cgroup)
-                    || match_str(&r.context_comment)
-                    || match_num(&r.data.nice)
-                    || match_num(&r.data.latency_nice)
-                    || match_num(&r.data.rtprio)
-                    || match_num(&r.data.oom_score_adj)
+    // Rules in the target file stay where they are, the others are appended to it before leaving their files.
+    pub fn plan_bulk_action(&self, selected_rules: &[EnrichedRule], action: &BulkAction) -> Result<SavePlan>
+    {
+        if selected_rules.is_empty()
+        {
+            bail!("No rules selected");
+        }
+
+        let (rules, _) = self.load_rules()?;
+
+        let mut rules_per_file: BTreeMap<&Path, Vec<&EnrichedRule>> = BTreeMap::new();
+
+        for rule in selected_rules
+        {
+            rules_per_file.entry(&rule.source_file).or_default().push(rule);
+        }
+
+        let mut changes = Vec::new();
+
+        if let BulkAction::MoveTo(target_file) = action
+        {
+            let target_file = self.resolve_target_file(target_file)?;
+            rules_per_file.remove(target_file.as_path());
+
+            let moved_rules: Vec<(&AnanicyRule, Option<&str>)> = rules_per_file
+                .values()
+                .flatten()
+                .map(|rule| (&rule.data, rule.context_comment.as_deref()))
+                .collect();
+
+            if moved_rules.is_empty()
+            {
+                bail!("The selected rules are already in {:?}", target_file);
+            }
+
+            changes.push(self.rule_repository.plan_append_rules(&target_file, &moved_rules)?);
+        }
+
+        for (source_file, file_rules) in rules_per_file
+        {
+            let edits = file_rules
+                .into_iter()
+                .map(|rule| {
+                    let edit = match action
+                    {
+                        BulkAction::SetField { field, value } =>
+                        {
+                            let mut data = rule.data.clone();
+                            data.set_field(field, value)?;
+                            data.validate()
+                                .with_context(|| format!("Rule {:?}", rule.data.name.as_deref().unwrap_or_default()))?;
+
+                            RuleLineEdit::Replace(data)
+                        }
+                        BulkAction::Disable => RuleLineEdit::Disable,
+                        BulkAction::MoveTo(_) | BulkAction::Delete => RuleLineEdit::Remove,
+                    };
+
+                    Ok((rule, edit))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            changes.push(self.rule_repository.plan_edit_rule_lines(source_file, &edits)?);
+        }
+
+        self.plan_changes(&rules, changes)
+    }
+
+    // Marks name a rule by its file and name, which still hold once edits shift the lines of the file.
+    pub fn mark_key(rule: &EnrichedRule) -> (PathBuf, String)
+    {
+        (rule.source_file.clone(), rule.data.name.clone().unwrap_or_default())
+    }
+
+    pub fn marked_rules(rules: &[EnrichedRule], marks: &HashSet<(PathBuf, String)>) -> Vec<EnrichedRule>
+    {
+        rules
+            .iter()
+            .filter(|rule| marks.contains(&Self::mark_key(rule)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn apply_save_plan(&self, save_plan: &SavePlan) -> Result<()>
+    {
+        self.rule_repository.apply_changes(&save_plan.changes)
+    }
+
+    // Pairs the changes with the rules they newly shadow, compared with the currently loaded rules.
+    fn plan_changes(&self, rules: &[EnrichedRule], changes: Vec<FileChange>) -> Result<SavePlan>
+    {
+        let (mut rules_after_save, _) = self.rule_repository.load_all_with_changes(&changes)?;
+        self.mark_shadowed_rules(&mut rules_after_save);
+
+        let shadowed_rules = rules_after_save
+            .into_iter()
+            .filter(|rule_after_save| {
+                rule_after_save.shadowed
+                    && !rules.iter().any(|rule| {
+                        rule.shadowed
+                            && rule.source_file == rule_after_save.source_file
+                            && rule.data.name == rule_after_save.data.name
+                    })
+            })
+            .collect();
+
+        Ok(SavePlan {
+            changes,
+            shadowed_rules,
+        })
+    }
+
+    pub fn rule_roots(&self) -> &[PathBuf]
+    {
+        self.rule_repository.base_paths()
+    }
+
+    // Every root with the folders and files below it that hold rules, parents listed before their children.
+    pub fn rule_tree(roots: &[PathBuf], rules: &[EnrichedRule]) -> Vec<RuleTreeNode>
+    {
+        let mut nodes = Vec::new();
+
+        for root in roots
+        {
+            let root_rules: Vec<&EnrichedRule> =
+                rules.iter().filter(|rule| rule.source_file.starts_with(root)).collect();
+
+            // Comparing paths component by component lists every folder right before its contents.
+            let mut paths = BTreeSet::new();
+
+            for rule in &root_rules
+            {
+                paths.extend(
+                    rule.source_file
+                        .ancestors()
+                        .take_while(|path| *path != root.as_path())
+                        .map(Path::to_path_buf),
+                );
+            }
+
+            nodes.push(RuleTreeNode {
+                kind: RuleTreeNodeKind::Root,
+                path: root.clone(),
+                depth: 0,
+                rule_count: root_rules.len(),
             });
+
+            for path in paths
+            {
+                let is_file = root_rules.iter().any(|rule| rule.source_file == path);
+
+                nodes.push(RuleTreeNode {
+                    kind: if is_file
+                    {
+                        RuleTreeNodeKind::File
+                    }
+                    else
+                    {
+                        RuleTreeNodeKind::Directory
+                    },
+                    depth: path
+                        .strip_prefix(root)
+                        .map_or(0, |relative| relative.components().count()),
+                    rule_count: root_rules
+                        .iter()
+                        .filter(|rule| rule.source_file.starts_with(&path))
+                        .count(),
+                    path,
+                });
+            }
+        }
+
+        nodes
+    }
+
+    // The files new rules can be added to, in the order rules are listed.
+    pub fn rule_files(rules: &[EnrichedRule]) -> Vec<PathBuf>
+    {
+        let mut rule_files: Vec<PathBuf> = rules.iter().map(|rule| rule.source_file.clone()).collect();
+
+        rule_files.sort();
+        rule_files.dedup();
+
+        rule_files
+    }
+
+    fn resolve_target_file(&self, target_file: &Path) -> Result<PathBuf>
+    {
+        let target_file = self.rule_repository.resolve_rule_file(target_file);
+
+        if target_file.extension().is_none_or(|extension| extension != "rules")
+        {
+            bail!("Target file {:?} must have the .rules extension", target_file);
         }
 
+        Ok(target_file)
+    }
+
+    pub fn find_rule(&self, rule_name: &str) -> Result<EnrichedRule>
+    {
+        let (rules, _) = self.load_rules()?;
+
+        match rules
+            .into_iter()
+            .find(|rule| !rule.shadowed && rule.data.name.as_deref() == Some(rule_name))
+        {
+            Some(rule) => Ok(rule),
+            None => bail!("Rule {:?} not found", rule_name),
+        }
+    }
+
+    pub fn add_rule(&self, rule: &AnanicyRule, target_file: Option<&Path>) -> Result<PathBuf>
+    {
+        let rule_name = rule.name.as_deref().unwrap_or_default();
+
+        if let Ok(existing_rule) = self.find_rule(rule_name)
+        {
+            bail!(
+                "Rule {:?} already exists in {:?}, use edit to change it",
+                rule_name,
+                existing_rule.source_file
+            );
+        }
+
+        self.save_rule(rule, target_file)
+    }
+
+    pub fn edit_rule(&self, rule_name: &str, changes: &[(String, String)]) -> Result<EnrichedRule>
+    {
+        let original = self.find_rule(rule_name)?;
+        let mut rule = original.clone();
+
+        for (field, value) in changes
+        {
+            rule.data.set_field(field, value)?;
+        }
+
+        // Taking the name of another rule would shadow it or leave the edited rule shadowed.
+        if let Some(new_name) = rule.data.name.as_deref()
+            && new_name != rule_name
+        {
+            let (rules, _) = self.load_rules()?;
+
+            if let Some(existing_rule) = rules
+                .iter()
+                .find(|existing_rule| existing_rule.data.name.as_deref() == Some(new_name))
+            {
+                bail!("Rule {:?} already exists in {:?}", new_name, existing_rule.source_file);
+            }
+        }
+
+        let save_plan = self.plan_edit_rule(&original, &rule.data, None)?;
+        self.apply_save_plan(&save_plan)?;
+
+        Ok(rule)
+    }
+
+    pub fn remove_rule(&self, rule_name: &str) -> Result<PathBuf>
+    {
+        let rule = self.find_rule(rule_name)?;
+
+        let save_plan = self.plan_bulk_action(slice::from_ref(&rule), &BulkAction::Delete)?;
+        self.apply_save_plan(&save_plan)?;
+
+        Ok(rule.source_file)
+    }
+
+    pub fn move_rule(&self, rule_name: &str, target_file: &Path) -> Result<PathBuf>
+    {
+        let rule = self.find_rule(rule_name)?;
+        let target_file = self.resolve_target_file(target_file)?;
+
+        if target_file == rule.source_file
+        {
+            return Ok(target_file);
+        }
+
+        // Both files are written by one apply_changes, which checks them before writing either.
+        let save_plan = self.plan_edit_rule(&rule, &rule.data, Some(&target_file))?;
+        self.apply_save_plan(&save_plan)?;
+
+        Ok(target_file)
+    }
+
+    pub fn load_cgroup_definitions(&self) -> Result<(Vec<CgroupDefinition>, Vec<Diagnostic>)>
+    {
+        self.rule_repository.load_cgroup_definitions()
+    }
+
+    pub fn load_type_definitions(&self) -> Result<(Vec<TypeDefinition>, Vec<Diagnostic>)>
+    {
+        self.rule_repository.load_type_definitions()
+    }
+
+    // ananicy-cpp lets later definitions replace earlier ones with the same name.
+    pub fn find_cgroup_definition<'a>(
+        definitions: &'a [CgroupDefinition],
+        cgroup_name: &str,
+    ) -> Option<&'a CgroupDefinition>
+    {
+        definitions
+            .iter()
+            .rev()
+            .find(|definition| definition.name.as_deref() == Some(cgroup_name))
+    }
+
+    pub fn load_rules(&self) -> Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>
+    {
+        let (mut rules, errors) = self.rule_repository.load_all()?;
+        self.mark_shadowed_rules(&mut rules);
         self.sort_rules(&mut rules);
 
         Ok((rules, errors))
     }
 
-    fn mark_shadowed_rules(&self, rules: &mut Vec<EnrichedRule>)
+    pub fn search_rules(
+        &self,
+        query: &str,
+        search_mode: SearchMode,
+        is_active: impl Fn(&str) -> bool,
+    ) -> Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>
+    {
+        let query = Query::parse(query)?;
+        let (rules, errors) = self.load_rules()?;
+
+        Ok((Self::rank_rules(&rules, &query, search_mode, is_active), errors))
+    }
+
+    // Best matches first, rules with the same score keep the category and name order.
+    pub fn rank_rules(
+        rules: &[EnrichedRule],
+        query: &Query,
+        search_mode: SearchMode,
+        is_active: impl Fn(&str) -> bool,
+    ) -> Vec<EnrichedRule>
+    {
+        let mut scored_rules: Vec<(u32, &EnrichedRule)> = rules
+            .iter()
+            .filter_map(|rule| query.score(rule, &is_active, search_mode).map(|score| (score, rule)))
+            .collect();
+
+        scored_rules.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        scored_rules.into_iter().map(|(_, rule)| rule.clone()).collect()
+    }
+
+    // A stable sort, so rules that compare equal keep their ranking. Rules without a value sort last either way.
+    pub fn sort_rules_by(rules: &mut [EnrichedRule], sort: RuleSort, is_active: impl Fn(&str) -> bool)
+    {
+        let text = |rule: &EnrichedRule| match sort.column
+        {
+            RuleColumn::Name => rule.data.name.as_deref().map(str::to_lowercase),
+            RuleColumn::Type => rule.data.rule_type.as_deref().map(str::to_lowercase),
+            RuleColumn::Sched => rule.data.sched.as_deref().map(str::to_lowercase),
+            RuleColumn::IoClass => rule.data.ioclass.as_deref().map(str::to_lowercase),
+            RuleColumn::Cgroup => rule.data.cgroup.as_deref().map(str::to_lowercase),
+            RuleColumn::Category => Some(rule.category().to_lowercase()),
+            _ => None,
+        };
+
+        let number = |rule: &EnrichedRule| match sort.column
+        {
+            RuleColumn::Nice => rule.data.nice,
+            RuleColumn::LatencyNice => rule.data.latency_nice,
+            RuleColumn::OomScoreAdj => rule.data.oom_score_adj,
+            RuleColumn::Active => Some(i32::from(
+                !rule.shadowed && rule.data.name.as_deref().is_some_and(&is_active),
+            )),
+            _ => None,
+        };
+
+        rules.sort_by(|a, b| match sort.column
+        {
+            RuleColumn::Nice | RuleColumn::LatencyNice | RuleColumn::OomScoreAdj | RuleColumn::Active =>
+            {
+                compare_values(number(a), number(b), sort.descending)
+            }
+            _ => compare_values(text(a), text(b), sort.descending),
+        });
+    }
+
+    // Shadowed rules never apply, so they do not count as active.
+    pub fn compute_statistics(
+        rules: &[EnrichedRule],
+        errors: &[Diagnostic],
+        is_active: impl Fn(&str) -> bool,
+    ) -> RuleStatistics
+    {
+        let mut statistics = RuleStatistics {
+            total_rules: rules.len(),
+            parse_errors: errors.len(),
+            ..Default::default()
+        };
+
+        for rule in rules
+        {
+            *statistics
+                .rules_per_category
+                .entry(rule.category().to_string())
+                .or_default() += 1;
+            *statistics.rules_per_file.entry(rule.source_file.clone()).or_default() += 1;
+            *statistics
+                .rules_per_type
+                .entry(rule.data.rule_type.clone().unwrap_or_else(|| "-".to_string()))
+                .or_default() += 1;
+
+            let histograms = [
+                (&mut statistics.nice_histogram, rule.data.nice),
+                (&mut statistics.latency_nice_histogram, rule.data.latency_nice),
+                (&mut statistics.oom_score_adj_histogram, rule.data.oom_score_adj),
+            ];
+
+            for (histogram, value) in histograms
+            {
+                if let Some(value) = value
+                {
+                    *histogram.entry(value).or_default() += 1;
+                }
+            }
+
+            if rule.shadowed
+            {
+                statistics.shadowed_rules += 1;
+            }
+            else if is_active(rule.data.name.as_deref().unwrap_or_default())
+            {
+                statistics.active_rules += 1;
+            }
+        }
+
+        statistics
+    }
+
+    fn mark_shadowed_rules(&self, rules: &mut [EnrichedRule])
     {
         let mut name_indices: std::collections::HashMap<String, Vec<usize>> = std::collections::HashMap::new();
 
@@ -81,7 +579,7 @@ impl RuleService
         }
     }
 
-    fn sort_rules(&self, rules: &mut Vec<EnrichedRule>)
+    fn sort_rules(&self, rules: &mut [EnrichedRule])
     {
         rules.sort_by(|a, b| {
             let get_folder = |path: &std::path::Path| {
@@ -110,3 +608,15 @@ impl RuleService
         });
     }
 }
+
+fn compare_values<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> std::cmp::Ordering
+{
+    match (a, b)
+    {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
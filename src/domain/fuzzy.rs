@@ -0,0 +1,190 @@
+// Scores how well a lower case pattern matches a text. Substrings rank above scattered subsequences such as
+// "stmwbh" in "steamwebhelper", which rank above matches that need a typo fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch
+{
+    pub score: u32,
+    // Char indices into the text, empty for typo matches.
+    pub positions: Vec<usize>,
+}
+
+const SUBSTRING_SCORE: u32 = 100;
+const SUBSEQUENCE_SCORE: u32 = 60;
+const TYPO_SCORE: u32 = 20;
+
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch>
+{
+    let pattern = fold_case(pattern);
+    let text = fold_case(text);
+
+    match_substring(&pattern, &text)
+        .or_else(|| match_subsequence(&pattern, &text))
+        .or_else(|| match_with_typos(&pattern, &text))
+}
+
+// Only accepts the pattern as a whole, for long texts where nearly any short pattern is a subsequence.
+pub fn substring_match(pattern: &str, text: &str) -> Option<FuzzyMatch>
+{
+    let pattern = fold_case(pattern);
+    let text = fold_case(text);
+
+    match_substring(&pattern, &text)
+}
+
+// Lowercases one char at a time, keeping the first char where lowercasing yields several (as for 'İ'), so
+// positions stay indices into the original text.
+fn fold_case(text: &str) -> Vec<char>
+{
+    text.chars()
+        .map(|character| character.to_lowercase().next().unwrap_or(character))
+        .collect()
+}
+
+fn match_substring(pattern: &[char], text: &[char]) -> Option<FuzzyMatch>
+{
+    if pattern.is_empty()
+    {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let start = text.windows(pattern.len()).position(|window| window == pattern)?;
+
+    let mut score = SUBSTRING_SCORE;
+
+    if start == 0
+    {
+        score += 50;
+    }
+
+    if text.len() == pattern.len()
+    {
+        score += 100;
+    }
+    else if is_word_start(text, start)
+    {
+        score += 20;
+    }
+
+    Some(FuzzyMatch {
+        score,
+        positions: (start..start + pattern.len()).collect(),
+    })
+}
+
+fn match_subsequence(pattern: &[char], text: &[char]) -> Option<FuzzyMatch>
+{
+    // Find where the earliest complete match ends, then walk back from there to the latest start so the match
+    // covers the shortest window.
+    let mut pattern_index = 0;
+    let mut end = None;
+
+    for (text_index, character) in text.iter().enumerate()
+    {
+        if *character == pattern[pattern_index]
+        {
+            pattern_index += 1;
+
+            if pattern_index == pattern.len()
+            {
+                end = Some(text_index);
+                break;
+            }
+        }
+    }
+
+    let end = end?;
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut pattern_index = pattern.len();
+
+    for text_index in (0..=end).rev()
+    {
+        if pattern_index > 0 && text[text_index] == pattern[pattern_index - 1]
+        {
+            pattern_index -= 1;
+            positions.push(text_index);
+        }
+    }
+
+    positions.reverse();
+
+    let mut score = SUBSEQUENCE_SCORE;
+
+    for (index, position) in positions.iter().enumerate()
+    {
+        if index > 0 && positions[index - 1] + 1 == *position
+        {
+            score += 4;
+        }
+
+        if is_word_start(text, *position)
+        {
+            score += 4;
+        }
+    }
+
+    let gaps = (end + 1 - positions[0] - pattern.len()) as u32;
+    score = score.saturating_sub(gaps).clamp(TYPO_SCORE + 1, SUBSTRING_SCORE - 1);
+
+    Some(FuzzyMatch { score, positions })
+}
+
+fn match_with_typos(pattern: &[char], text: &[char]) -> Option<FuzzyMatch>
+{
+    let allowed_typos = match pattern.len()
+    {
+        0..=3 => return None,
+        4..=7 => 1,
+        _ => 2,
+    };
+
+    let distance = substring_edit_distance(pattern, text);
+
+    (distance <= allowed_typos).then(|| FuzzyMatch {
+        score: TYPO_SCORE - 5 * distance as u32,
+        positions: Vec::new(),
+    })
+}
+
+// Edit distance between the pattern and the closest part of the text, counting a swap of neighbours as one edit.
+fn substring_edit_distance(pattern: &[char], text: &[char]) -> usize
+{
+    let columns = text.len() + 1;
+    let mut rows = vec![vec![0; columns]; pattern.len() + 1];
+
+    for (pattern_index, row) in rows.iter_mut().enumerate()
+    {
+        row[0] = pattern_index;
+    }
+
+    for pattern_index in 1..=pattern.len()
+    {
+        for text_index in 1..columns
+        {
+            let cost = usize::from(pattern[pattern_index - 1] != text[text_index - 1]);
+
+            let mut distance = (rows[pattern_index - 1][text_index] + 1)
+                .min(rows[pattern_index][text_index - 1] + 1)
+                .min(rows[pattern_index - 1][text_index - 1] + cost);
+
+            if pattern_index > 1
+                && text_index > 1
+                && pattern[pattern_index - 1] == text[text_index - 2]
+                && pattern[pattern_index - 2] == text[text_index - 1]
+            {
+                distance = distance.min(rows[pattern_index - 2][text_index - 2] + 1);
+            }
+
+            rows[pattern_index][text_index] = distance;
+        }
+    }
+
+    rows[pattern.len()].iter().copied().min().unwrap_or(pattern.len())
+}
+
+fn is_word_start(text: &[char], index: usize) -> bool
+{
+    index == 0 || !text[index - 1].is_alphanumeric()
+}
@@ -1 +1,4 @@
+pub mod fuzzy;
 pub mod models;
+pub mod pagination;
+pub mod query;
@@ -1,37 +1,198 @@
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize, Serializer};
+use similar::TextDiff;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AnanicyRule
 {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub rule_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nice: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub latency_nice: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sched: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rtprio: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ioclass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub oom_score_adj: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cgroup: Option<String>,
 }
 
-impl Default for AnanicyRule
+impl AnanicyRule
 {
-    fn default() -> Self
+    pub const FIELDS: [&'static str; 9] = [
+        "name",
+        "type",
+        "nice",
+        "latency_nice",
+        "sched",
+        "rtprio",
+        "ioclass",
+        "oom_score_adj",
+        "cgroup",
+    ];
+    pub const SCHED_POLICIES: [&'static str; 7] = ["normal", "other", "batch", "idle", "fifo", "rr", "iso"];
+    pub const IO_CLASSES: [&'static str; 4] = ["realtime", "best-effort", "idle", "none"];
+
+    pub fn get_field(&self, field: &str) -> Option<String>
     {
-        Self {
-            name: None,
-            rule_type: None,
-            nice: None,
-            latency_nice: None,
-            sched: None,
-            rtprio: None,
-            ioclass: None,
-            oom_score_adj: None,
-            cgroup: None,
+        match field
+        {
+            "name" => self.name.clone(),
+            "type" => self.rule_type.clone(),
+            "nice" => self.nice.map(|v| v.to_string()),
+            "latency_nice" => self.latency_nice.map(|v| v.to_string()),
+            "sched" => self.sched.clone(),
+            "rtprio" => self.rtprio.map(|v| v.to_string()),
+            "ioclass" => self.ioclass.clone(),
+            "oom_score_adj" => self.oom_score_adj.map(|v| v.to_string()),
+            "cgroup" => self.cgroup.clone(),
+            _ => None,
         }
     }
+
+    // An empty value clears the field.
+    pub fn set_field(&mut self, field: &str, value: &str) -> Result<()>
+    {
+        let value = value.trim();
+        let text = (!value.is_empty()).then(|| value.to_string());
+        let number = || -> Result<Option<i32>> {
+            text.as_deref()
+                .map(|v| {
+                    v.parse()
+                        .map_err(|_| anyhow!("{} must be a number, got '{}'", field, v))
+                })
+                .transpose()
+        };
+
+        match field
+        {
+            "name" => self.name = text,
+            "type" => self.rule_type = text,
+            "nice" => self.nice = number()?,
+            "latency_nice" => self.latency_nice = number()?,
+            "sched" => self.sched = text,
+            "rtprio" => self.rtprio = number()?,
+            "ioclass" => self.ioclass = text,
+            "oom_score_adj" => self.oom_score_adj = number()?,
+            "cgroup" => self.cgroup = text,
+            _ => bail!(
+                "Unknown field '{}', expected one of: {}",
+                field,
+                Self::FIELDS.join(", ")
+            ),
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()>
+    {
+        let problems: Vec<String> = self.problems().into_iter().map(|(_, problem)| problem).collect();
+
+        if problems.is_empty()
+        {
+            Ok(())
+        }
+        else
+        {
+            bail!("Invalid rule: {}", problems.join("; "))
+        }
+    }
+
+    // Every invalid value, paired with the field it belongs to.
+    pub fn problems(&self) -> Vec<(&'static str, String)>
+    {
+        let mut problems = Vec::new();
+
+        match self.name.as_deref()
+        {
+            None | Some("") => problems.push(("name", "name is required".to_string())),
+            Some(name) if name.contains('/') =>
+            {
+                problems.push(("name", format!("name '{}' must not contain '/'", name)))
+            }
+            _ =>
+            {}
+        }
+
+        let mut check_range = |field: &'static str, value: Option<i32>, min: i32, max: i32| {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                problems.push((
+                    field,
+                    format!("{} must be between {} and {}, got {}", field, min, max, value),
+                ));
+            }
+        };
+
+        check_range("nice", self.nice, -20, 19);
+        check_range("latency_nice", self.latency_nice, -20, 19);
+        check_range("rtprio", self.rtprio, 1, 99);
+        check_range("oom_score_adj", self.oom_score_adj, -1000, 1000);
+
+        if let Some(sched) = self.sched.as_deref()
+            && !Self::SCHED_POLICIES.contains(&sched)
+        {
+            problems.push((
+                "sched",
+                format!(
+                    "sched must be one of {}, got '{}'",
+                    Self::SCHED_POLICIES.join(", "),
+                    sched
+                ),
+            ));
+        }
+
+        if let Some(ioclass) = self.ioclass.as_deref()
+            && !Self::IO_CLASSES.contains(&ioclass)
+        {
+            problems.push((
+                "ioclass",
+                format!(
+                    "ioclass must be one of {}, got '{}'",
+                    Self::IO_CLASSES.join(", "),
+                    ioclass
+                ),
+            ));
+        }
+
+        problems
+    }
+}
+
+// A line of a .types file, the defaults rules pick up through their type.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TypeDefinition
+{
+    #[serde(rename = "type")]
+    pub name: Option<String>,
+    pub nice: Option<i32>,
+    pub latency_nice: Option<i32>,
+    pub sched: Option<String>,
+    pub ioclass: Option<String>,
+    pub cgroup: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CgroupDefinition
+{
+    #[serde(rename = "cgroup")]
+    pub name: Option<String>,
+    #[serde(rename = "CPUQuota")]
+    pub cpu_quota: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,19 +201,723 @@ pub struct EnrichedRule
     pub data: AnanicyRule,
     pub context_comment: Option<String>,
     pub source_file: PathBuf,
+    // 1-based, as editors count lines.
+    pub line_number: usize,
     pub shadowed: bool,
 }
 
+impl EnrichedRule
+{
+    // The directory holding the rule file, e.g. "00-default".
+    pub fn category(&self) -> &str
+    {
+        self.source_file
+            .parent()
+            .and_then(|path| path.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or("root")
+    }
+}
+
+// A problem found while loading rule files, with the file and line it points at when there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic
+{
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+}
+
+impl Diagnostic
+{
+    pub fn new(message: String) -> Self
+    {
+        Self {
+            message,
+            file: None,
+            line: None,
+        }
+    }
+
+    pub fn in_file(file: &Path, line: Option<usize>, message: String) -> Self
+    {
+        Self {
+            message,
+            file: Some(file.to_path_buf()),
+            line,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str(&self.message)
+    }
+}
+
+// Machine readable output keeps listing errors as plain messages.
+impl Serialize for Diagnostic
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    {
+        serializer.collect_str(self)
+    }
+}
+
+// A rule file before and after a pending write. A file that does not exist yet has empty old content.
 #[derive(Debug, Clone)]
+pub struct FileChange
+{
+    pub file: PathBuf,
+    pub old_content: String,
+    pub new_content: String,
+}
+
+impl FileChange
+{
+    pub fn unified_diff(&self) -> String
+    {
+        let file = self.file.display().to_string();
+
+        TextDiff::from_lines(&self.old_content, &self.new_content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&file, &file)
+            .to_string()
+    }
+}
+
+// What saving a rule or a bulk action writes, and the rules that become shadowed once it is written.
+#[derive(Debug, Clone)]
+pub struct SavePlan
+{
+    pub changes: Vec<FileChange>,
+    pub shadowed_rules: Vec<EnrichedRule>,
+}
+
+// What happens to the line a rule was loaded from.
+#[derive(Debug, Clone)]
+pub enum RuleLineEdit
+{
+    Replace(AnanicyRule),
+    // Turns the line into a comment, which ananicy-cpp skips.
+    Disable,
+    Remove,
+}
+
+// Applied to every selected rule at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction
+{
+    SetField
+    {
+        field: String,
+        value: String,
+    },
+    MoveTo(PathBuf),
+    Disable,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleTreeNodeKind
+{
+    Root,
+    Directory,
+    File,
+}
+
+// A config root, a folder below it or a .rules file, counting the rules it holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleTreeNode
+{
+    pub kind: RuleTreeNodeKind,
+    pub path: PathBuf,
+    // Root nodes are at depth 0.
+    pub depth: usize,
+    pub rule_count: usize,
+}
+
+impl RuleTreeNode
+{
+    pub fn label(&self) -> String
+    {
+        match self.kind
+        {
+            RuleTreeNodeKind::Root => self.path.display().to_string(),
+            _ => self
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn contains(&self, rule: &EnrichedRule) -> bool
+    {
+        rule.source_file.starts_with(&self.path)
+    }
+}
+
+// A column of the rules table, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleColumn
+{
+    Name,
+    Type,
+    Nice,
+    LatencyNice,
+    Sched,
+    IoClass,
+    OomScoreAdj,
+    Cgroup,
+    Category,
+    Active,
+}
+
+impl RuleColumn
+{
+    pub const ALL: [RuleColumn; 10] = [
+        RuleColumn::Name,
+        RuleColumn::Type,
+        RuleColumn::Nice,
+        RuleColumn::LatencyNice,
+        RuleColumn::Sched,
+        RuleColumn::IoClass,
+        RuleColumn::OomScoreAdj,
+        RuleColumn::Cgroup,
+        RuleColumn::Category,
+        RuleColumn::Active,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSort
+{
+    pub column: RuleColumn,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleStatistics
+{
+    pub total_rules: usize,
+    pub rules_per_category: BTreeMap<String, usize>,
+    pub rules_per_file: BTreeMap<PathBuf, usize>,
+    pub rules_per_type: BTreeMap<String, usize>,
+    pub nice_histogram: BTreeMap<i32, usize>,
+    pub latency_nice_histogram: BTreeMap<i32, usize>,
+    pub oom_score_adj_histogram: BTreeMap<i32, usize>,
+    pub shadowed_rules: usize,
+    pub parse_errors: usize,
+    pub active_rules: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleReport
+{
+    pub rule: AnanicyRule,
+    pub category: String,
+    pub source_file: PathBuf,
+    pub shadowed: bool,
+    pub context_comment: Option<String>,
+    pub processes: Vec<ProcessStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStatus
+{
+    #[serde(flatten)]
+    pub info: ProcessInfo,
+    pub matched_by: MatchKind,
+    // Rule fields whose live value differs from the rule.
+    pub mismatches: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat
+{
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl OutputFormat
+{
+    pub fn is_machine_readable(&self) -> bool
+    {
+        *self != OutputFormat::Table
+    }
+}
+
+impl fmt::Display for OutputFormat
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            OutputFormat::Table => f.write_str("table"),
+            OutputFormat::Json => f.write_str("json"),
+            OutputFormat::Ndjson => f.write_str("ndjson"),
+            OutputFormat::Csv => f.write_str("csv"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat
+{
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err>
+    {
+        match value.to_lowercase().as_str()
+        {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "Unknown output format '{}', expected 'json', 'ndjson', 'csv' or 'table'",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeReading<T>
+{
+    Value(T),
+    // Also what older snapshots get for readings they predate.
+    #[default]
+    Unsupported,
+    PermissionDenied,
+    Gone,
+}
+
+impl<T> AttributeReading<T>
+{
+    pub fn value(&self) -> Option<&T>
+    {
+        match self
+        {
+            AttributeReading::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_value(&self) -> bool
+    {
+        matches!(self, AttributeReading::Value(_))
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> AttributeReading<U>
+    {
+        match self
+        {
+            AttributeReading::Value(value) => AttributeReading::Value(f(value)),
+            AttributeReading::Unsupported => AttributeReading::Unsupported,
+            AttributeReading::PermissionDenied => AttributeReading::PermissionDenied,
+            AttributeReading::Gone => AttributeReading::Gone,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for AttributeReading<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            AttributeReading::Value(value) => value.fmt(f),
+            AttributeReading::Unsupported => f.write_str("unsupported"),
+            AttributeReading::PermissionDenied => f.write_str("permission denied"),
+            AttributeReading::Gone => f.write_str("process gone"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo
 {
     pub process_id: i32,
     pub name: String,
-    pub nice: Option<i32>,
-    pub oom_score_adj: Option<i32>,
-    pub cgroup: Option<String>,
-    pub sched_policy: Option<String>,
-    pub rtprio: Option<i32>,
-    pub ioclass: Option<String>,
-    pub latency_nice: Option<i32>,
+    pub nice: AttributeReading<i32>,
+    pub oom_score_adj: AttributeReading<i32>,
+    pub cgroup: AttributeReading<String>,
+    pub sched_policy: AttributeReading<String>,
+    pub rtprio: AttributeReading<i32>,
+    pub ioclass: AttributeReading<String>,
+    pub latency_nice: AttributeReading<i32>,
+    #[serde(default)]
+    pub autogroup: AttributeReading<Autogroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Autogroup
+{
+    pub id: u64,
+    pub nice: i32,
+}
+
+impl fmt::Display for Autogroup
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "autogroup-{} nice {}", self.id, self.nice)
+    }
+}
+
+// Variants are ordered from the most to the least reliable way a rule name can match a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind
+{
+    Comm,
+    ExeSuffix,
+    Truncated,
+    ArgvBasename
+    {
+        index: usize,
+    },
+}
+
+impl MatchKind
+{
+    // Any argument whose basename equals the rule name matches, e.g. a script path handed to an interpreter.
+    pub fn is_weak(&self) -> bool
+    {
+        matches!(self, MatchKind::ArgvBasename { .. })
+    }
+}
+
+impl fmt::Display for MatchKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            MatchKind::Comm => f.write_str("comm"),
+            MatchKind::ExeSuffix => f.write_str("comm with .exe suffix"),
+            MatchKind::Truncated => f.write_str("truncated comm"),
+            MatchKind::ArgvBasename { index } => write!(f, "argv[{}] basename", index),
+        }
+    }
+}
+
+// Strict mirrors the ananicy-cpp daemon, which only looks at the process comm. Discovery also accepts the
+// looser matches that help to find candidates for new rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchingMode
+{
+    #[default]
+    Strict,
+    Discovery,
+}
+
+impl MatchingMode
+{
+    pub fn allows(&self, match_kind: MatchKind) -> bool
+    {
+        match self
+        {
+            MatchingMode::Strict => matches!(match_kind, MatchKind::Comm | MatchKind::Truncated),
+            MatchingMode::Discovery => true,
+        }
+    }
+}
+
+impl fmt::Display for MatchingMode
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            MatchingMode::Strict => f.write_str("strict"),
+            MatchingMode::Discovery => f.write_str("discovery"),
+        }
+    }
+}
+
+impl FromStr for MatchingMode
+{
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err>
+    {
+        match value.to_lowercase().as_str()
+        {
+            "strict" => Ok(MatchingMode::Strict),
+            "discovery" => Ok(MatchingMode::Discovery),
+            _ => Err(format!(
+                "Unknown matching mode '{}', expected 'strict' or 'discovery'",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme
+{
+    #[default]
+    Dark,
+    Light,
+    Monochrome,
+}
+
+impl fmt::Display for Theme
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            Theme::Dark => f.write_str("dark"),
+            Theme::Light => f.write_str("light"),
+            Theme::Monochrome => f.write_str("monochrome"),
+        }
+    }
+}
+
+impl FromStr for Theme
+{
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err>
+    {
+        match value.to_lowercase().as_str()
+        {
+            "dark" => Ok(Theme::Dark),
+            "light" => Ok(Theme::Light),
+            "monochrome" => Ok(Theme::Monochrome),
+            _ => Err(format!(
+                "Unknown theme '{}', expected 'dark', 'light' or 'monochrome'",
+                value
+            )),
+        }
+    }
+}
+
+// Shorter refresh intervals would rescan /proc nearly without pause.
+pub const MIN_TICK_RATE_MS: u64 = 100;
+
+// Settings from config.toml. Every key is optional, command line flags take precedence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig
+{
+    pub language: String,
+    // Later roots win when rule names clash, so new rules are written to the last one.
+    pub rule_roots: Vec<PathBuf>,
+    pub custom_rules_dir: String,
+    pub items_per_page: usize,
+    pub tick_rate_ms: u64,
+    pub output_format: OutputFormat,
+    pub theme: Theme,
+    pub matching: MatchingMode,
+    // How the editor is told the line, e.g. "+{line}" or "-g {file}:{line}". Unset, it is picked by the editor.
+    pub editor_line_arg: Option<String>,
+}
+
+impl Default for AppConfig
+{
+    fn default() -> Self
+    {
+        Self {
+            language: "en".to_string(),
+            rule_roots: vec![PathBuf::from("/etc/ananicy.d")],
+            custom_rules_dir: "99-custom".to_string(),
+            items_per_page: 50,
+            tick_rate_ms: 1000,
+            output_format: OutputFormat::Table,
+            theme: Theme::Dark,
+            matching: MatchingMode::Strict,
+            editor_line_arg: None,
+        }
+    }
+}
+
+impl AppConfig
+{
+    pub fn validate(&self) -> Result<()>
+    {
+        if self.items_per_page == 0
+        {
+            bail!("items_per_page must be at least 1");
+        }
+
+        if self.tick_rate_ms < MIN_TICK_RATE_MS
+        {
+            bail!(
+                "tick_rate_ms must be at least {}, got {}",
+                MIN_TICK_RATE_MS,
+                self.tick_rate_ms
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRecord
+{
+    #[serde(flatten)]
+    pub info: ProcessInfo,
+    pub cmdline: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub parent_process_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTreeNode
+{
+    pub process_id: i32,
+    pub parent_process_id: Option<i32>,
+    pub name: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSnapshot
+{
+    pub captured_at: String,
+    pub hostname: Option<String>,
+    pub kernel_version: Option<String>,
+    #[serde(default)]
+    pub capabilities: Option<SystemCapabilities>,
+    pub processes: Vec<ProcessRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockDeviceScheduler
+{
+    pub device: String,
+    pub scheduler: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemCapabilities
+{
+    pub latency_nice: bool,
+    pub cgroup2_mount: Option<PathBuf>,
+    pub cgroup2_controllers: Vec<String>,
+    pub block_devices: Vec<BlockDeviceScheduler>,
+    pub autogroup_enabled: Option<bool>,
+    pub ananicy_binary: Option<PathBuf>,
+    pub ananicy_service_unit: Option<PathBuf>,
+}
+
+impl SystemCapabilities
+{
+    pub fn supports_cgroup_cpu(&self) -> bool
+    {
+        self.cgroup2_mount.is_some() && self.cgroup2_controllers.iter().any(|controller| controller == "cpu")
+    }
+
+    pub fn supports_ioclass(&self) -> bool
+    {
+        // Only BFQ and mq-deadline look at the I/O priority class.
+        self.block_devices.is_empty()
+            || self
+                .block_devices
+                .iter()
+                .any(|device| device.scheduler == "bfq" || device.scheduler == "mq-deadline")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuMax
+{
+    pub quota: Option<u64>,
+    pub period: u64,
+}
+
+impl fmt::Display for CpuMax
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self.quota
+        {
+            Some(quota) => write!(f, "{} {}", quota, self.period),
+            None => write!(f, "max {}", self.period),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pressure
+{
+    pub some_avg10: f64,
+    pub full_avg10: Option<f64>,
+}
+
+impl fmt::Display for Pressure
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "some {:.2}", self.some_avg10)?;
+
+        if let Some(full_avg10) = self.full_avg10
+        {
+            write!(f, " full {:.2}", full_avg10)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupResources
+{
+    pub path: String,
+    pub online_cpus: usize,
+    pub cpu_max: AttributeReading<CpuMax>,
+    pub cpu_weight: AttributeReading<u64>,
+    pub memory_max: AttributeReading<String>,
+    pub io_weight: AttributeReading<String>,
+    pub cpu_pressure: AttributeReading<Pressure>,
+    pub memory_pressure: AttributeReading<Pressure>,
+    pub io_pressure: AttributeReading<Pressure>,
+}
+
+// Attributes of the processes the UI shows, read off the UI thread.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessAttributes
+{
+    pub infos: HashMap<i32, ProcessInfo>,
+    pub cgroup_resources: HashMap<i32, CgroupResources>,
+}
+
+impl CgroupResources
+{
+    // ananicy-cpp spreads CPUQuota over all online CPUs, so 50 on a 4 core machine becomes a cpu.max of 2 CPUs.
+    pub fn cpu_quota_percent(&self) -> Option<f64>
+    {
+        let cpu_max = self.cpu_max.value()?;
+        let quota = cpu_max.quota?;
+
+        if cpu_max.period == 0 || self.online_cpus == 0
+        {
+            return None;
+        }
+
+        Some(quota as f64 / cpu_max.period as f64 / self.online_cpus as f64 * 100.0)
+    }
+
+    pub fn enforces_cpu_quota(&self, cpu_quota: u32) -> bool
+    {
+        self.cpu_quota_percent()
+            .is_some_and(|percent| (percent - cpu_quota as f64).abs() < 1.0)
+    }
 }
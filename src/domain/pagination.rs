@@ -0,0 +1,112 @@
+use std::ops::Range;
+
+// Borders and the header row of the rules table.
+const TABLE_CHROME_ROWS: u16 = 3;
+
+// Splits the rule list into pages the size of the rules table. The selection is a row on the current page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination
+{
+    pub items_per_page: usize,
+    pub current_page: usize,
+}
+
+impl Pagination
+{
+    pub fn new(items_per_page: usize) -> Self
+    {
+        Self {
+            items_per_page: items_per_page.max(1),
+            current_page: 0,
+        }
+    }
+
+    // Rows left for rules in a table of the given height, capped by the configured page size. A table too short
+    // for its header still shows one rule per page, so paging keeps working.
+    pub fn rows_for_height(table_height: u16, max_items_per_page: usize) -> usize
+    {
+        (table_height.saturating_sub(TABLE_CHROME_ROWS) as usize).clamp(1, max_items_per_page.max(1))
+    }
+
+    pub fn page_count(&self, item_count: usize) -> usize
+    {
+        item_count.div_ceil(self.items_per_page).max(1)
+    }
+
+    pub fn page_range(&self, item_count: usize) -> Range<usize>
+    {
+        let start = (self.current_page * self.items_per_page).min(item_count);
+
+        start..(start + self.items_per_page).min(item_count)
+    }
+
+    pub fn index_of(&self, row: usize) -> usize
+    {
+        self.current_page * self.items_per_page + row
+    }
+
+    // Turns to the page holding the index, clamped to the list, and returns its row there.
+    pub fn select(&mut self, index: usize, item_count: usize) -> Option<usize>
+    {
+        if item_count == 0
+        {
+            self.current_page = 0;
+            return None;
+        }
+
+        let index = index.min(item_count - 1);
+        self.current_page = index / self.items_per_page;
+
+        Some(index % self.items_per_page)
+    }
+
+    // Changes the page size, keeping the selected rule, or else the first visible one, on screen. Returns the new
+    // row of the selection.
+    pub fn resize(&mut self, items_per_page: usize, selected_row: Option<usize>, item_count: usize) -> Option<usize>
+    {
+        let items_per_page = items_per_page.max(1);
+
+        if items_per_page == self.items_per_page
+        {
+            return selected_row;
+        }
+
+        let first_visible = self.index_of(0);
+        let selected_index = selected_row.map(|row| self.index_of(row));
+        self.items_per_page = items_per_page;
+
+        match selected_index
+        {
+            Some(index) => self.select(index, item_count),
+            None =>
+            {
+                self.current_page = first_visible / items_per_page;
+                None
+            }
+        }
+    }
+
+    pub fn next_page(&mut self, item_count: usize) -> bool
+    {
+        let has_next = self.current_page + 1 < self.page_count(item_count);
+
+        if has_next
+        {
+            self.current_page += 1;
+        }
+
+        has_next
+    }
+
+    pub fn previous_page(&mut self) -> bool
+    {
+        let has_previous = self.current_page > 0;
+
+        if has_previous
+        {
+            self.current_page -= 1;
+        }
+
+        has_previous
+    }
+}
@@ -0,0 +1,644 @@
+use crate::domain::fuzzy::{self, FuzzyMatch};
+use crate::domain::models::EnrichedRule;
+
+use anyhow::{Result, bail};
+use std::iter::Peekable;
+use std::str::Chars;
+
+// A parsed search such as `type:Game nice:<0 (sched:idle OR ioclass:idle) NOT shadowed:yes "quoted text"`.
+// Adjacent terms are combined with AND, the keywords AND, OR and NOT must be upper case.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query
+{
+    All,
+    Term(Term),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term
+{
+    Text(String),
+    Field
+    {
+        field: TextField,
+        value: String,
+        exact: bool,
+    },
+    Number
+    {
+        field: NumberField,
+        comparison: Comparison,
+        value: i32,
+    },
+    Active(bool),
+    Shadowed(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextField
+{
+    Name,
+    Type,
+    Sched,
+    IoClass,
+    Cgroup,
+    File,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberField
+{
+    Nice,
+    LatencyNice,
+    RtPrio,
+    OomScoreAdj,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison
+{
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode
+{
+    #[default]
+    Exact,
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token
+{
+    Word(String),
+    Quoted(String),
+    Open,
+    Close,
+}
+
+impl Query
+{
+    pub const FIELDS: [&'static str; 13] = [
+        "name",
+        "type",
+        "nice",
+        "latency_nice",
+        "sched",
+        "rtprio",
+        "ioclass",
+        "oom_score_adj",
+        "cgroup",
+        "file",
+        "comment",
+        "active",
+        "shadowed",
+    ];
+
+    pub fn parse(input: &str) -> Result<Query>
+    {
+        let tokens = tokenize(input)?;
+
+        if tokens.is_empty()
+        {
+            return Ok(Query::All);
+        }
+
+        let mut parser = Parser { tokens, position: 0 };
+        let query = parser.parse_or()?;
+
+        if let Some(token) = parser.peek()
+        {
+            bail!("Unexpected {} in query", describe_token(token));
+        }
+
+        Ok(query)
+    }
+
+    // Activity changes with every process scan, while everything else only changes with the rules.
+    pub fn depends_on_processes(&self) -> bool
+    {
+        match self
+        {
+            Query::All => false,
+            Query::Term(term) => matches!(term, Term::Active(_)),
+            Query::Not(query) => query.depends_on_processes(),
+            Query::And(queries) | Query::Or(queries) => queries.iter().any(Query::depends_on_processes),
+        }
+    }
+
+    // Higher scores rank first and None filters the rule out. Exact searches give every match the same score.
+    pub fn score(&self, rule: &EnrichedRule, is_active: &impl Fn(&str) -> bool, search_mode: SearchMode)
+    -> Option<u32>
+    {
+        match search_mode
+        {
+            SearchMode::Exact => self.matches(rule, is_active).then_some(0),
+            SearchMode::Fuzzy => self.fuzzy_score(rule, is_active),
+        }
+    }
+
+    fn fuzzy_score(&self, rule: &EnrichedRule, is_active: &impl Fn(&str) -> bool) -> Option<u32>
+    {
+        match self
+        {
+            Query::All => Some(0),
+            Query::Term(term) => term.fuzzy_score(rule, is_active),
+            Query::Not(query) => query.fuzzy_score(rule, is_active).is_none().then_some(0),
+            Query::And(queries) => queries.iter().map(|query| query.fuzzy_score(rule, is_active)).sum(),
+            Query::Or(queries) => queries
+                .iter()
+                .filter_map(|query| query.fuzzy_score(rule, is_active))
+                .max(),
+        }
+    }
+
+    // Char indices of a rule name that the search terms matched, for highlighting.
+    pub fn highlight(&self, rule_name: &str, search_mode: SearchMode) -> Vec<usize>
+    {
+        let mut positions = Vec::new();
+        self.collect_highlights(rule_name, search_mode, &mut positions);
+
+        positions.sort_unstable();
+        positions.dedup();
+
+        positions
+    }
+
+    fn collect_highlights(&self, rule_name: &str, search_mode: SearchMode, positions: &mut Vec<usize>)
+    {
+        match self
+        {
+            Query::Term(Term::Text(text))
+            | Query::Term(Term::Field {
+                field: TextField::Name,
+                value: text,
+                ..
+            }) =>
+            {
+                let text_match = match search_mode
+                {
+                    SearchMode::Exact => fuzzy::substring_match(text, rule_name),
+                    SearchMode::Fuzzy => fuzzy::fuzzy_match(text, rule_name),
+                };
+
+                positions.extend(text_match.map(|text_match| text_match.positions).unwrap_or_default());
+            }
+            Query::And(queries) | Query::Or(queries) =>
+            {
+                for query in queries
+                {
+                    query.collect_highlights(rule_name, search_mode, positions);
+                }
+            }
+            Query::All | Query::Term(_) | Query::Not(_) =>
+            {}
+        }
+    }
+
+    // `is_active` tells whether a process currently runs under the given rule name.
+    pub fn matches(&self, rule: &EnrichedRule, is_active: &impl Fn(&str) -> bool) -> bool
+    {
+        match self
+        {
+            Query::All => true,
+            Query::Term(term) => term.matches(rule, is_active),
+            Query::Not(query) => !query.matches(rule, is_active),
+            Query::And(queries) => queries.iter().all(|query| query.matches(rule, is_active)),
+            Query::Or(queries) => queries.iter().any(|query| query.matches(rule, is_active)),
+        }
+    }
+}
+
+impl Term
+{
+    fn matches(&self, rule: &EnrichedRule, is_active: &impl Fn(&str) -> bool) -> bool
+    {
+        match self
+        {
+            // Free text looks into the text fields, numbers only match a whole value.
+            Term::Text(text) =>
+            {
+                let number = text.parse::<i32>().ok();
+
+                [
+                    TextField::Name,
+                    TextField::Type,
+                    TextField::Sched,
+                    TextField::IoClass,
+                    TextField::Cgroup,
+                    TextField::Comment,
+                ]
+                .into_iter()
+                .filter_map(|field| field.value(rule))
+                .any(|value| value.to_lowercase().contains(text))
+                    || number.is_some_and(|number| {
+                        [
+                            NumberField::Nice,
+                            NumberField::LatencyNice,
+                            NumberField::RtPrio,
+                            NumberField::OomScoreAdj,
+                        ]
+                        .into_iter()
+                        .any(|field| field.value(rule) == Some(number))
+                    })
+            }
+            Term::Field { field, value, exact } => field.value(rule).is_some_and(|field_value| {
+                let field_value = field_value.to_lowercase();
+
+                if *exact
+                {
+                    field_value == *value
+                }
+                else
+                {
+                    field_value.contains(value)
+                }
+            }),
+            Term::Number {
+                field,
+                comparison,
+                value,
+            } => field
+                .value(rule)
+                .is_some_and(|field_value| comparison.holds(field_value, *value)),
+            Term::Active(active) => is_active(rule.data.name.as_deref().unwrap_or_default()) == *active,
+            Term::Shadowed(shadowed) => rule.shadowed == *shadowed,
+        }
+    }
+}
+
+impl Term
+{
+    // Names and types are short enough for subsequence and typo matching, longer texts only match substrings.
+    fn fuzzy_score(&self, rule: &EnrichedRule, is_active: &impl Fn(&str) -> bool) -> Option<u32>
+    {
+        let Term::Text(text) = self
+        else
+        {
+            return self.matches(rule, is_active).then_some(0);
+        };
+
+        let weighted_fields = [
+            (
+                TextField::Name,
+                4,
+                fuzzy::fuzzy_match as fn(&str, &str) -> Option<FuzzyMatch>,
+            ),
+            (TextField::Type, 2, fuzzy::fuzzy_match),
+            (TextField::Sched, 2, fuzzy::substring_match),
+            (TextField::IoClass, 2, fuzzy::substring_match),
+            (TextField::Cgroup, 2, fuzzy::substring_match),
+            (TextField::Comment, 1, fuzzy::substring_match),
+        ];
+
+        let text_score = weighted_fields
+            .into_iter()
+            .filter_map(|(field, weight, match_text)| {
+                let value = field.value(rule)?;
+                match_text(text, &value).map(|text_match| text_match.score * weight)
+            })
+            .max();
+
+        // Numbers still only match whole values.
+        let number_score = self.matches(rule, is_active).then_some(200);
+
+        text_score.max(number_score)
+    }
+}
+
+impl TextField
+{
+    fn value(self, rule: &EnrichedRule) -> Option<String>
+    {
+        match self
+        {
+            TextField::Name => rule.data.name.clone(),
+            TextField::Type => rule.data.rule_type.clone(),
+            TextField::Sched => rule.data.sched.clone(),
+            TextField::IoClass => rule.data.ioclass.clone(),
+            TextField::Cgroup => rule.data.cgroup.clone(),
+            TextField::File => Some(rule.source_file.to_string_lossy().into_owned()),
+            TextField::Comment => rule.context_comment.clone(),
+        }
+    }
+}
+
+impl NumberField
+{
+    fn value(self, rule: &EnrichedRule) -> Option<i32>
+    {
+        match self
+        {
+            NumberField::Nice => rule.data.nice,
+            NumberField::LatencyNice => rule.data.latency_nice,
+            NumberField::RtPrio => rule.data.rtprio,
+            NumberField::OomScoreAdj => rule.data.oom_score_adj,
+        }
+    }
+}
+
+impl Comparison
+{
+    fn holds(self, left: i32, right: i32) -> bool
+    {
+        match self
+        {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+struct Parser
+{
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser
+{
+    fn peek(&self) -> Option<&Token>
+    {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token>
+    {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool
+    {
+        matches!(self.peek(), Some(Token::Word(word)) if word == keyword)
+    }
+
+    fn parse_or(&mut self) -> Result<Query>
+    {
+        let mut queries = vec![self.parse_and()?];
+
+        while self.peek_keyword("OR")
+        {
+            self.next();
+            queries.push(self.parse_and()?);
+        }
+
+        Ok(Self::combine(queries, Query::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<Query>
+    {
+        let mut queries = vec![self.parse_unary()?];
+
+        loop
+        {
+            match self.peek()
+            {
+                None | Some(Token::Close) => break,
+                _ if self.peek_keyword("OR") => break,
+                _ if self.peek_keyword("AND") =>
+                {
+                    self.next();
+                }
+                _ =>
+                {}
+            }
+
+            queries.push(self.parse_unary()?);
+        }
+
+        Ok(Self::combine(queries, Query::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<Query>
+    {
+        match self.next()
+        {
+            Some(Token::Word(word)) if word == "NOT" => Ok(Query::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Word(word)) if word == "AND" || word == "OR" =>
+            {
+                bail!("Expected a search term before '{}'", word)
+            }
+            Some(Token::Word(word)) => parse_term(&word).map(Query::Term),
+            Some(Token::Quoted(text)) => Ok(Query::Term(Term::Text(text.to_lowercase()))),
+            Some(Token::Open) =>
+            {
+                let query = self.parse_or()?;
+
+                match self.next()
+                {
+                    Some(Token::Close) => Ok(query),
+                    _ => bail!("Missing ')' in query"),
+                }
+            }
+            Some(Token::Close) => bail!("Unexpected ')' in query"),
+            None => bail!("Query ends where a search term was expected"),
+        }
+    }
+
+    fn combine(mut queries: Vec<Query>, group: fn(Vec<Query>) -> Query) -> Query
+    {
+        if queries.len() == 1
+        {
+            queries.remove(0)
+        }
+        else
+        {
+            group(queries)
+        }
+    }
+}
+
+fn describe_token(token: &Token) -> String
+{
+    match token
+    {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Quoted(text) => format!("\"{}\"", text),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>>
+{
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&character) = chars.peek()
+    {
+        match character
+        {
+            c if c.is_whitespace() =>
+            {
+                chars.next();
+            }
+            '(' =>
+            {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' =>
+            {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' =>
+            {
+                chars.next();
+                tokens.push(Token::Quoted(read_quoted(&mut chars)?));
+            }
+            _ =>
+            {
+                let mut word = String::new();
+
+                while let Some(&c) = chars.peek()
+                {
+                    if c.is_whitespace() || c == '(' || c == ')'
+                    {
+                        break;
+                    }
+
+                    chars.next();
+
+                    // Allows quoted field values such as comment:"web browser".
+                    if c == '"'
+                    {
+                        word.push_str(&read_quoted(&mut chars)?);
+                    }
+                    else
+                    {
+                        word.push(c);
+                    }
+                }
+
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn read_quoted(chars: &mut Peekable<Chars>) -> Result<String>
+{
+    let mut text = String::new();
+
+    for c in chars.by_ref()
+    {
+        if c == '"'
+        {
+            return Ok(text);
+        }
+
+        text.push(c);
+    }
+
+    bail!("Missing closing '\"' in query")
+}
+
+fn parse_term(word: &str) -> Result<Term>
+{
+    let Some((field, value)) = word.split_once(':')
+    else
+    {
+        return Ok(Term::Text(word.to_lowercase()));
+    };
+
+    let field = field.to_lowercase();
+
+    if value.is_empty()
+    {
+        bail!("Missing value for '{}:'", field);
+    }
+
+    let text_field = match field.as_str()
+    {
+        "name" => Some(TextField::Name),
+        "type" => Some(TextField::Type),
+        "sched" => Some(TextField::Sched),
+        "ioclass" | "io" => Some(TextField::IoClass),
+        "cgroup" => Some(TextField::Cgroup),
+        "file" => Some(TextField::File),
+        "comment" => Some(TextField::Comment),
+        _ => None,
+    };
+
+    if let Some(text_field) = text_field
+    {
+        let (exact, value) = match value.strip_prefix('=')
+        {
+            Some(exact_value) => (true, exact_value),
+            None => (false, value),
+        };
+
+        return Ok(Term::Field {
+            field: text_field,
+            value: value.to_lowercase(),
+            exact,
+        });
+    }
+
+    let number_field = match field.as_str()
+    {
+        "nice" => NumberField::Nice,
+        "latency_nice" | "latency" => NumberField::LatencyNice,
+        "rtprio" => NumberField::RtPrio,
+        "oom_score_adj" | "oom" => NumberField::OomScoreAdj,
+        "active" => return parse_flag(&field, value).map(Term::Active),
+        "shadowed" => return parse_flag(&field, value).map(Term::Shadowed),
+        _ => bail!(
+            "Unknown field '{}', expected one of: {}",
+            field,
+            Query::FIELDS.join(", ")
+        ),
+    };
+
+    let (comparison, number) = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("!=", Comparison::NotEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(operator, comparison)| value.strip_prefix(operator).map(|number| (comparison, number)))
+    .unwrap_or((Comparison::Equal, value));
+
+    match number.parse()
+    {
+        Ok(number) => Ok(Term::Number {
+            field: number_field,
+            comparison,
+            value: number,
+        }),
+        Err(_) => bail!("{} must be compared with a number, got '{}'", field, number),
+    }
+}
+
+fn parse_flag(field: &str, value: &str) -> Result<bool>
+{
+    match value.to_lowercase().as_str()
+    {
+        "yes" | "true" | "1" => Ok(true),
+        "no" | "false" | "0" => Ok(false),
+        _ => bail!("{} expects yes or no, got '{}'", field, value),
+    }
+}
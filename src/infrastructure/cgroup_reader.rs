@@ -0,0 +1,99 @@
+use crate::domain::models::{AttributeReading, CgroupResources, CpuMax, Pressure};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct CgroupReader
+{
+    mount_path: PathBuf,
+}
+
+impl CgroupReader
+{
+    pub fn new(mount_path: PathBuf) -> Self
+    {
+        Self { mount_path }
+    }
+
+    pub fn read(&self, cgroup_path: &str, online_cpus: usize) -> CgroupResources
+    {
+        let cgroup_dir = self.mount_path.join(cgroup_path.trim_start_matches('/'));
+
+        CgroupResources {
+            path: cgroup_path.to_string(),
+            online_cpus,
+            cpu_max: Self::parse(Self::read_file(&cgroup_dir, "cpu.max"), Self::parse_cpu_max),
+            cpu_weight: Self::parse(Self::read_file(&cgroup_dir, "cpu.weight"), |content| {
+                content.trim().parse().ok()
+            }),
+            memory_max: Self::read_file(&cgroup_dir, "memory.max").map(|content| content.trim().to_string()),
+            io_weight: Self::read_file(&cgroup_dir, "io.weight")
+                .map(|content| content.lines().next().unwrap_or("").trim().to_string()),
+            cpu_pressure: Self::parse(Self::read_file(&cgroup_dir, "cpu.pressure"), Self::parse_pressure),
+            memory_pressure: Self::parse(Self::read_file(&cgroup_dir, "memory.pressure"), Self::parse_pressure),
+            io_pressure: Self::parse(Self::read_file(&cgroup_dir, "io.pressure"), Self::parse_pressure),
+        }
+    }
+
+    fn read_file(cgroup_dir: &Path, name: &str) -> AttributeReading<String>
+    {
+        match fs::read_to_string(cgroup_dir.join(name))
+        {
+            Ok(content) => AttributeReading::Value(content),
+            // A missing interface file means the controller is not enabled for this cgroup, a missing
+            // directory means the cgroup has been removed since the process was read.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && cgroup_dir.is_dir() => AttributeReading::Unsupported,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AttributeReading::Gone,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => AttributeReading::PermissionDenied,
+            Err(_) => AttributeReading::Unsupported,
+        }
+    }
+
+    fn parse<T>(reading: AttributeReading<String>, parser: impl FnOnce(&str) -> Option<T>) -> AttributeReading<T>
+    {
+        match reading
+        {
+            AttributeReading::Value(content) =>
+            {
+                parser(&content).map_or(AttributeReading::Unsupported, AttributeReading::Value)
+            }
+            AttributeReading::Unsupported => AttributeReading::Unsupported,
+            AttributeReading::PermissionDenied => AttributeReading::PermissionDenied,
+            AttributeReading::Gone => AttributeReading::Gone,
+        }
+    }
+
+    // "max 100000" or "50000 100000"
+    fn parse_cpu_max(content: &str) -> Option<CpuMax>
+    {
+        let mut cpu_max_parts = content.split_whitespace();
+
+        let quota = match cpu_max_parts.next()?
+        {
+            "max" => None,
+            quota => Some(quota.parse().ok()?),
+        };
+        let period = cpu_max_parts.next()?.parse().ok()?;
+
+        Some(CpuMax { quota, period })
+    }
+
+    // "some avg10=0.00 avg60=0.00 avg300=0.00 total=0" followed by an optional "full ..." line.
+    fn parse_pressure(content: &str) -> Option<Pressure>
+    {
+        let avg10 = |kind: &str| {
+            content
+                .lines()
+                .find(|line| line.starts_with(kind))?
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("avg10="))?
+                .parse()
+                .ok()
+        };
+
+        Some(Pressure {
+            some_avg10: avg10("some")?,
+            full_avg10: avg10("full"),
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use crate::domain::models::AppConfig;
+
+use anyhow::{Context, Result, bail};
+use directories::ProjectDirs;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct ConfigRepository
+{
+    file_path: Option<PathBuf>,
+    // Only a file asked for with --config has to exist.
+    required: bool,
+}
+
+impl Default for ConfigRepository
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl ConfigRepository
+{
+    // $XDG_CONFIG_HOME/ananicy-rule-o-matic/config.toml, usually ~/.config/ananicy-rule-o-matic/config.toml.
+    pub fn new() -> Self
+    {
+        Self {
+            file_path: ProjectDirs::from("", "", "ananicy-rule-o-matic")
+                .map(|project_dirs| project_dirs.config_dir().join("config.toml")),
+            required: false,
+        }
+    }
+
+    pub fn new_with_path(file_path: PathBuf) -> Self
+    {
+        Self {
+            file_path: Some(file_path),
+            required: true,
+        }
+    }
+
+    pub fn new_with_default_path(file_path: PathBuf) -> Self
+    {
+        Self {
+            file_path: Some(file_path),
+            required: false,
+        }
+    }
+
+    // A missing file at the default location simply means the defaults apply.
+    pub fn load(&self) -> Result<AppConfig>
+    {
+        let Some(file_path) = &self.file_path
+        else
+        {
+            return Ok(AppConfig::default());
+        };
+
+        if !file_path.exists()
+        {
+            if self.required
+            {
+                bail!("Config file {:?} does not exist", file_path);
+            }
+
+            return Ok(AppConfig::default());
+        }
+
+        let content =
+            fs::read_to_string(file_path).with_context(|| format!("Failed to read config file {:?}", file_path))?;
+
+        let config: AppConfig =
+            toml::from_str(&content).with_context(|| format!("Failed to parse config file {:?}", file_path))?;
+
+        config
+            .validate()
+            .with_context(|| format!("Invalid config file {:?}", file_path))?;
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+// Editors that take the line as "+LINE" before the file.
+const PLUS_LINE_EDITORS: &[&str] = &[
+    "vi",
+    "vim",
+    "nvim",
+    "view",
+    "gvim",
+    "nano",
+    "emacs",
+    "emacsclient",
+    "micro",
+    "kak",
+    "joe",
+    "jed",
+    "ne",
+    "mg",
+];
+
+// Editors that want "-g FILE:LINE" instead.
+const GOTO_LINE_EDITORS: &[&str] = &["code", "code-insiders", "codium", "vscodium"];
+
+pub struct EditorLauncher
+{
+    command: String,
+    line_argument: Option<String>,
+}
+
+impl Default for EditorLauncher
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl EditorLauncher
+{
+    pub fn new() -> Self
+    {
+        Self::new_with_line_argument(None)
+    }
+
+    // $VISUAL, then $EDITOR, then vi. The line argument is a template like "+{line}" or "-g {file}:{line}", without
+    // one it is picked by the editor's name.
+    pub fn new_with_line_argument(line_argument: Option<String>) -> Self
+    {
+        let command = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|command| !command.trim().is_empty())
+            .unwrap_or_else(|| "vi".to_string());
+
+        Self::new_with_command(command, line_argument)
+    }
+
+    pub fn new_with_command(command: String, line_argument: Option<String>) -> Self
+    {
+        Self { command, line_argument }
+    }
+
+    // The command may carry its own arguments, e.g. "code --wait". Editors that are not known to take a line only
+    // get the file, so they do not open "+LINE" as a second one.
+    pub fn open(&self, file: &Path, line: Option<usize>) -> Result<()>
+    {
+        let mut words = self.command.split_whitespace();
+        let program = words.next().context("The editor command is empty")?;
+
+        let mut command = Command::new(program);
+        command.args(words);
+
+        let file_name = file.to_string_lossy();
+
+        match line.zip(self.line_template(program))
+        {
+            Some((line, template)) =>
+            {
+                let arguments: Vec<String> = template
+                    .split_whitespace()
+                    .map(|word| word.replace("{line}", &line.to_string()).replace("{file}", &file_name))
+                    .collect();
+
+                command.args(&arguments);
+
+                if !template.contains("{file}")
+                {
+                    command.arg(file);
+                }
+            }
+            None =>
+            {
+                command.arg(file);
+            }
+        }
+
+        let status = command
+            .status()
+            .with_context(|| format!("Failed to start editor {:?}", self.command))?;
+
+        if !status.success()
+        {
+            bail!("Editor {:?} exited with {}", self.command, status);
+        }
+
+        Ok(())
+    }
+
+    fn line_template(&self, program: &str) -> Option<&str>
+    {
+        if let Some(line_argument) = &self.line_argument
+        {
+            return Some(line_argument.as_str());
+        }
+
+        let name = Path::new(program).file_name()?.to_str()?;
+
+        if PLUS_LINE_EDITORS.contains(&name)
+        {
+            Some("+{line}")
+        }
+        else if GOTO_LINE_EDITORS.contains(&name)
+        {
+            Some("-g {file}:{line}")
+        }
+        else
+        {
+            None
+        }
+    }
+}
@@ -1 +1,8 @@
+pub mod cgroup_reader;
+pub mod config_repository;
+pub mod editor_launcher;
+pub mod proc_scanner;
+pub mod process_reader;
 pub mod rule_repository;
+pub mod snapshot_repository;
+pub mod system_probe;
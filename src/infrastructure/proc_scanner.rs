@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+// Processes frequently exec() right after fork(), so names are re-read for a few scans after a PID first
+// shows up. Everything else is only re-read on the periodic resync, or when the start time in stat shows the PID
+// now belongs to another process.
+const YOUNG_PROCESS_SCANS: u64 = 2;
+const FULL_RESYNC_SCANS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcEntry
+{
+    pub name: String,
+    pub cmdline: Vec<String>,
+    pub parent_process_id: i32,
+    // Processes of one session share an autogroup.
+    pub session_id: i32,
+    // Clock ticks after boot, a new value for a known PID means the PID was reused.
+    start_time: Option<u64>,
+    first_seen_scan: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanDelta
+{
+    pub added: Vec<(i32, ProcEntry)>,
+    pub removed: Vec<(i32, ProcEntry)>,
+}
+
+#[derive(Debug, Default)]
+struct ProcStat
+{
+    parent_process_id: i32,
+    session_id: i32,
+    start_time: Option<u64>,
+}
+
+impl ScanDelta
+{
+    pub fn is_empty(&self) -> bool
+    {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+pub struct ProcScanner
+{
+    proc_path: PathBuf,
+    entries: HashMap<i32, ProcEntry>,
+    scan_count: u64,
+}
+
+impl Default for ProcScanner
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl ProcScanner
+{
+    pub fn new() -> Self
+    {
+        Self::new_with_proc_path(PathBuf::from("/proc"))
+    }
+
+    pub fn new_with_proc_path(proc_path: PathBuf) -> Self
+    {
+        Self {
+            proc_path,
+            entries: HashMap::new(),
+            scan_count: 0,
+        }
+    }
+
+    pub fn entries(&self) -> &HashMap<i32, ProcEntry>
+    {
+        &self.entries
+    }
+
+    pub fn get(&self, process_id: i32) -> Option<&ProcEntry>
+    {
+        self.entries.get(&process_id)
+    }
+
+    pub fn scan(&mut self) -> ScanDelta
+    {
+        let mut delta = ScanDelta::default();
+
+        let Ok(proc_dir) = fs::read_dir(&self.proc_path)
+        else
+        {
+            return delta;
+        };
+
+        let full_resync = self.scan_count.is_multiple_of(FULL_RESYNC_SCANS);
+
+        let mut live_pids: Vec<i32> = proc_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.parse().ok()))
+            .collect();
+
+        live_pids.sort_unstable();
+
+        let live_lookup: HashSet<i32> = live_pids.iter().copied().collect();
+
+        let exited: Vec<i32> = self
+            .entries
+            .keys()
+            .filter(|pid| !live_lookup.contains(pid))
+            .copied()
+            .collect();
+
+        for pid in exited
+        {
+            if let Some(entry) = self.entries.remove(&pid)
+            {
+                delta.removed.push((pid, entry));
+            }
+        }
+
+        for pid in live_pids
+        {
+            let stat = self.read_stat(pid);
+
+            if self
+                .entries
+                .get(&pid)
+                .is_some_and(|entry| entry.start_time != stat.start_time)
+                && let Some(entry) = self.entries.remove(&pid)
+            {
+                delta.removed.push((pid, entry));
+            }
+
+            let needs_read = match self.entries.get(&pid)
+            {
+                Some(entry) =>
+                {
+                    full_resync
+                        || self.scan_count - entry.first_seen_scan < YOUNG_PROCESS_SCANS
+                        || entry.parent_process_id != stat.parent_process_id
+                        || entry.session_id != stat.session_id
+                }
+                None => true,
+            };
+
+            if !needs_read
+            {
+                continue;
+            }
+
+            let Some(name) = self.read_comm(pid)
+            else
+            {
+                // Exited between listing /proc and reading it.
+                if let Some(entry) = self.entries.remove(&pid)
+                {
+                    delta.removed.push((pid, entry));
+                }
+
+                continue;
+            };
+
+            let cmdline = self.read_cmdline(pid);
+
+            match self.entries.get_mut(&pid)
+            {
+                Some(entry) =>
+                {
+                    // Orphans get reparented and processes call setsid(), so these can change even if nothing
+                    // else does.
+                    if entry.name != name
+                        || entry.cmdline != cmdline
+                        || entry.parent_process_id != stat.parent_process_id
+                        || entry.session_id != stat.session_id
+                    {
+                        let previous = entry.clone();
+
+                        entry.name = name;
+                        entry.cmdline = cmdline;
+                        entry.parent_process_id = stat.parent_process_id;
+                        entry.session_id = stat.session_id;
+
+                        delta.removed.push((pid, previous));
+                        delta.added.push((pid, entry.clone()));
+                    }
+                }
+
+                None =>
+                {
+                    let entry = ProcEntry {
+                        name,
+                        cmdline,
+                        parent_process_id: stat.parent_process_id,
+                        session_id: stat.session_id,
+                        start_time: stat.start_time,
+                        first_seen_scan: self.scan_count,
+                    };
+
+                    self.entries.insert(pid, entry.clone());
+                    delta.added.push((pid, entry));
+                }
+            }
+        }
+
+        self.scan_count += 1;
+
+        delta
+    }
+
+    fn read_comm(&self, process_id: i32) -> Option<String>
+    {
+        let comm_path = self.proc_path.join(process_id.to_string()).join("comm");
+
+        fs::read_to_string(comm_path)
+            .ok()
+            .map(|content| content.trim_end_matches('\n').to_string())
+    }
+
+    // The parent process id (field 4), session id (field 6) and start time (field 22), zero and None when stat
+    // cannot be read.
+    fn read_stat(&self, process_id: i32) -> ProcStat
+    {
+        let stat_path = self.proc_path.join(process_id.to_string()).join("stat");
+
+        let Ok(content) = fs::read_to_string(stat_path)
+        else
+        {
+            return ProcStat::default();
+        };
+
+        // The comm field may contain spaces and parentheses, so the fields are counted from its closing one.
+        let fields: Vec<&str> = content
+            .rsplit_once(')')
+            .map(|(_, fields)| fields.split_whitespace().collect())
+            .unwrap_or_default();
+
+        ProcStat {
+            parent_process_id: fields.get(1).and_then(|field| field.parse().ok()).unwrap_or(0),
+            session_id: fields.get(3).and_then(|field| field.parse().ok()).unwrap_or(0),
+            start_time: fields.get(19).and_then(|field| field.parse().ok()),
+        }
+    }
+
+    fn read_cmdline(&self, process_id: i32) -> Vec<String>
+    {
+        let cmdline_path = self.proc_path.join(process_id.to_string()).join("cmdline");
+
+        let Ok(content) = fs::read(cmdline_path)
+        else
+        {
+            return Vec::new();
+        };
+
+        content
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect()
+    }
+}
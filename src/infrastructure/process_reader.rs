@@ -0,0 +1,291 @@
+use crate::domain::models::{AttributeReading, Autogroup, CgroupResources, ProcessInfo};
+use crate::infrastructure::cgroup_reader::CgroupReader;
+
+use libc::{SCHED_BATCH, SCHED_FIFO, SCHED_IDLE, SCHED_OTHER, SCHED_RR, SYS_ioprio_get, SYS_sched_getattr, syscall};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+// Reads the scheduling attributes of live processes from /proc and the scheduler syscalls.
+#[derive(Default)]
+pub struct ProcessReader;
+
+impl ProcessReader
+{
+    pub fn new() -> Self
+    {
+        Self
+    }
+
+    pub fn read_process_info(&self, process_id: i32, process_name: &str) -> ProcessInfo
+    {
+        let (sched_policy, rtprio, latency_nice) = self.read_scheduler_info(process_id);
+
+        ProcessInfo {
+            process_id,
+            name: process_name.to_string(),
+            nice: self.read_nice(process_id),
+            oom_score_adj: self.read_oom_score(process_id),
+            cgroup: self.read_cgroup(process_id),
+            sched_policy,
+            rtprio,
+            ioclass: self.read_io_priority(process_id),
+            latency_nice,
+            autogroup: self.read_autogroup(process_id),
+        }
+    }
+
+    // On hybrid hierarchies the reported cgroup may be a v1 path, the unified one is always the "0::" entry.
+    pub fn read_unified_cgroup(&self, process_id: i32) -> Option<String>
+    {
+        self.read_proc_file(process_id, "cgroup")
+            .value()?
+            .lines()
+            .find_map(|cgroup_line| cgroup_line.strip_prefix("0::"))
+            .map(str::to_string)
+    }
+
+    pub fn read_cgroup_resources(&self, cgroup_path: &str, cgroup2_mount: &Path) -> CgroupResources
+    {
+        let online_cpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as usize;
+
+        CgroupReader::new(cgroup2_mount.to_path_buf()).read(cgroup_path, online_cpus)
+    }
+
+    pub fn read_exe(&self, process_id: i32) -> Option<PathBuf>
+    {
+        fs::read_link(format!("/proc/{}/exe", process_id)).ok()
+    }
+
+    pub fn read_uid(&self, process_id: i32) -> Option<u32>
+    {
+        let status_content = fs::read_to_string(format!("/proc/{}/status", process_id)).ok()?;
+
+        status_content
+            .lines()
+            .find_map(|line| line.strip_prefix("Uid:"))
+            .and_then(|uids| uids.split_whitespace().next())
+            .and_then(|uid| uid.parse().ok())
+    }
+
+    pub fn read_kernel_value(&self, name: &str) -> Option<String>
+    {
+        fs::read_to_string(Path::new("/proc/sys/kernel").join(name))
+            .ok()
+            .map(|content| content.trim().to_string())
+    }
+
+    fn read_nice(&self, pid: i32) -> AttributeReading<i32>
+    {
+        unsafe {
+            // getpriority() can legitimately return -1, so errno is the only failure signal.
+            *libc::__errno_location() = 0;
+            let val = libc::getpriority(libc::PRIO_PROCESS, pid as u32);
+
+            if val == -1 && *libc::__errno_location() != 0
+            {
+                return Self::last_errno_reading();
+            }
+
+            AttributeReading::Value(val)
+        }
+    }
+
+    fn read_oom_score(&self, pid: i32) -> AttributeReading<i32>
+    {
+        match self.read_proc_file(pid, "oom_score_adj")
+        {
+            AttributeReading::Value(content) => match content.trim().parse()
+            {
+                Ok(oom_score_adj) => AttributeReading::Value(oom_score_adj),
+                Err(_) => AttributeReading::Unsupported,
+            },
+            other => other.map(|_| 0),
+        }
+    }
+
+    fn read_autogroup(&self, pid: i32) -> AttributeReading<Autogroup>
+    {
+        match self.read_proc_file(pid, "autogroup")
+        {
+            // Content looks like "/autogroup-42 nice 0", or is empty when the process sits in a cpu cgroup and
+            // autogroup does not apply to it.
+            AttributeReading::Value(content) =>
+            {
+                let autogroup_parts: Vec<&str> = content.split_whitespace().collect();
+
+                let autogroup = match autogroup_parts.as_slice()
+                {
+                    [name, "nice", nice] => name
+                        .strip_prefix("/autogroup-")
+                        .and_then(|id| id.parse().ok())
+                        .zip(nice.parse().ok())
+                        .map(|(id, nice)| Autogroup { id, nice }),
+                    _ => None,
+                };
+
+                autogroup.map_or(AttributeReading::Unsupported, AttributeReading::Value)
+            }
+            // Kernels built without CONFIG_SCHED_AUTOGROUP have no such file at all.
+            AttributeReading::Gone if Path::new(&format!("/proc/{}", pid)).exists() => AttributeReading::Unsupported,
+            other => other.map(|_| Autogroup { id: 0, nice: 0 }),
+        }
+    }
+
+    fn read_cgroup(&self, pid: i32) -> AttributeReading<String>
+    {
+        self.read_proc_file(pid, "cgroup").map(|cgroup_content| {
+            for cgroup_line in cgroup_content.lines()
+            {
+                let cgroup_line_parts: Vec<&str> = cgroup_line.split(':').collect();
+
+                if cgroup_line_parts.len() == 3
+                {
+                    let cgroup_path = cgroup_line_parts[2];
+                    if cgroup_path != "/" && !cgroup_path.is_empty()
+                    {
+                        return cgroup_path.to_string();
+                    }
+                }
+            }
+
+            "/".to_string()
+        })
+    }
+
+    pub fn read_scheduler_info(
+        &self,
+        process_id: i32,
+    ) -> (AttributeReading<String>, AttributeReading<i32>, AttributeReading<i32>)
+    {
+        unsafe {
+            let policy_result = libc::sched_getscheduler(process_id);
+            let policy = if policy_result >= 0
+            {
+                AttributeReading::Value(match policy_result
+                {
+                    SCHED_OTHER => "normal".to_string(),
+                    SCHED_FIFO => "fifo".to_string(),
+                    SCHED_RR => "rr".to_string(),
+                    SCHED_BATCH => "batch".to_string(),
+                    SCHED_IDLE => "idle".to_string(),
+                    6 => "deadline".to_string(),
+                    _ => format!("unknown({})", policy_result),
+                })
+            }
+            else
+            {
+                Self::last_errno_reading()
+            };
+
+            let mut sched_priority_param: libc::sched_param = std::mem::zeroed();
+            let rtprio = if libc::sched_getparam(process_id, &mut sched_priority_param) == 0
+            {
+                AttributeReading::Value(sched_priority_param.sched_priority)
+            }
+            else
+            {
+                Self::last_errno_reading()
+            };
+
+            #[repr(C)]
+            struct SchedAttr
+            {
+                size: u32,
+                sched_policy: u32,
+                sched_flags: u64,
+                sched_nice: i32,
+                sched_priority: u32,
+                sched_runtime: u64,
+                sched_deadline: u64,
+                sched_period: u64,
+                sched_util_min: u32,
+                sched_util_max: u32,
+                sched_latency_nice: i32,
+            }
+
+            let mut latency_nice_attribute: SchedAttr = std::mem::zeroed();
+            latency_nice_attribute.size = std::mem::size_of::<SchedAttr>() as u32;
+
+            let result = syscall(
+                SYS_sched_getattr,
+                process_id,
+                &mut latency_nice_attribute as *mut SchedAttr,
+                latency_nice_attribute.size,
+                0,
+            );
+
+            // The kernel reports how much of the struct it filled in. Kernels without latency_nice
+            // stop before that field instead of failing the call.
+            let latency_nice_end = std::mem::offset_of!(SchedAttr, sched_latency_nice) + std::mem::size_of::<i32>();
+
+            let latency_nice = if result != 0
+            {
+                match io::Error::last_os_error().raw_os_error()
+                {
+                    Some(libc::E2BIG) | Some(libc::EINVAL) | Some(libc::ENOSYS) => AttributeReading::Unsupported,
+                    errno => Self::reading_from_errno(errno),
+                }
+            }
+            else if (latency_nice_attribute.size as usize) < latency_nice_end
+            {
+                AttributeReading::Unsupported
+            }
+            else
+            {
+                AttributeReading::Value(latency_nice_attribute.sched_latency_nice)
+            };
+
+            (policy, rtprio, latency_nice)
+        }
+    }
+
+    fn read_io_priority(&self, process_id: i32) -> AttributeReading<String>
+    {
+        unsafe {
+            let io_priority = syscall(SYS_ioprio_get, 1, process_id);
+
+            if io_priority >= 0
+            {
+                let io_priority_value = io_priority as i32;
+                let ioclass_id = io_priority_value >> 13;
+
+                AttributeReading::Value(match ioclass_id
+                {
+                    0 => "none".to_string(),
+                    1 => "realtime".to_string(),
+                    2 => "best-effort".to_string(),
+                    3 => "idle".to_string(),
+                    _ => format!("unknown({})", ioclass_id),
+                })
+            }
+            else
+            {
+                Self::last_errno_reading()
+            }
+        }
+    }
+
+    fn read_proc_file(&self, process_id: i32, name: &str) -> AttributeReading<String>
+    {
+        match fs::read_to_string(format!("/proc/{}/{}", process_id, name))
+        {
+            Ok(content) => AttributeReading::Value(content),
+            Err(e) => Self::reading_from_errno(e.raw_os_error()),
+        }
+    }
+
+    fn last_errno_reading<T>() -> AttributeReading<T>
+    {
+        Self::reading_from_errno(io::Error::last_os_error().raw_os_error())
+    }
+
+    fn reading_from_errno<T>(errno: Option<i32>) -> AttributeReading<T>
+    {
+        match errno
+        {
+            Some(libc::ESRCH) | Some(libc::ENOENT) => AttributeReading::Gone,
+            Some(libc::EPERM) | Some(libc::EACCES) => AttributeReading::PermissionDenied,
+            _ => AttributeReading::Unsupported,
+        }
+    }
+}
@@ -1,106 +1,437 @@
-use crate::domain::models::{AnanicyRule, EnrichedRule};
+use crate::domain::models::{
+    AnanicyRule, AppConfig, CgroupDefinition, Diagnostic, EnrichedRule, FileChange, RuleLineEdit, TypeDefinition,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use serde::de::DeserializeOwned;
 use std::path::{Path, PathBuf};
-use std::{env, fs};
+use std::{env, fs, slice};
 use walkdir::WalkDir;
 
+// Disabled rules are commented out with their own marker, so the parser can tell them from context comments.
+pub const DISABLED_RULE_PREFIX: &str = "#disabled# ";
+
 pub struct RuleRepository
 {
-    base_path: PathBuf,
+    base_paths: Vec<PathBuf>,
+    custom_rules_dir: String,
+}
+
+impl Default for RuleRepository
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
 }
 
 impl RuleRepository
 {
     pub fn new() -> Self
+    {
+        Self::new_with_config(&AppConfig::default())
+    }
+
+    // ANANICY_CPP_CONFDIR still wins over the configured roots.
+    pub fn new_with_config(config: &AppConfig) -> Self
     {
         let environment_variable_key = "ANANICY_CPP_CONFDIR";
 
-        match env::var(environment_variable_key)
+        let base_paths = match env::var(environment_variable_key)
         {
-            Ok(configuration_directory_path) => Self {
-                base_path: PathBuf::from(configuration_directory_path),
-            },
+            Ok(configuration_directory_path) => vec![PathBuf::from(configuration_directory_path)],
+            Err(_) => config.rule_roots.clone(),
+        };
 
-            Err(_) => Self {
-                base_path: PathBuf::from("/etc/ananicy.d"),
-            },
+        Self {
+            base_paths,
+            custom_rules_dir: config.custom_rules_dir.clone(),
         }
     }
 
     pub fn new_with_base_path(base_path: PathBuf) -> Self
     {
-        Self { base_path }
+        Self::new_with_base_paths(vec![base_path], AppConfig::default().custom_rules_dir)
+    }
+
+    pub fn new_with_base_paths(base_paths: Vec<PathBuf>, custom_rules_dir: String) -> Self
+    {
+        Self {
+            base_paths,
+            custom_rules_dir,
+        }
+    }
+
+    // Rules from later roots shadow earlier ones, so edits that create files go to the last root.
+    pub fn base_paths(&self) -> &[PathBuf]
+    {
+        &self.base_paths
+    }
+
+    fn write_root(&self) -> &Path
+    {
+        self.base_paths.last().map(PathBuf::as_path).unwrap_or(Path::new("."))
     }
 
-    pub fn load_all(&self) -> Result<(Vec<EnrichedRule>, Vec<String>)>
+    pub fn load_all(&self) -> Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>
+    {
+        self.load_all_with(&[])
+    }
+
+    // The rules as they will be once the changes are written.
+    pub fn load_all_with_changes(&self, changes: &[FileChange]) -> Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>
+    {
+        self.load_all_with(changes)
+    }
+
+    fn load_all_with(&self, changes: &[FileChange]) -> Result<(Vec<EnrichedRule>, Vec<Diagnostic>)>
     {
         let mut rules = Vec::new();
         let mut errors = Vec::new();
 
-        if !self.base_path.exists()
+        for base_path in &self.base_paths
         {
-            errors.push(format!("Base path {:?} does not exist", self.base_path));
-
-            return Ok((rules, errors));
+            if !base_path.exists()
+            {
+                errors.push(Diagnostic::in_file(
+                    base_path,
+                    None,
+                    format!("Base path {:?} does not exist", base_path),
+                ));
+            }
         }
 
-        let mut files: Vec<_> = WalkDir::new(self.base_path.as_path())
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .collect();
+        let mut files = self.find_files("rules");
+
+        let sort_key = |file: &Path| {
+            let root_index = self
+                .base_paths
+                .iter()
+                .position(|base_path| file.starts_with(base_path))
+                .unwrap_or(self.base_paths.len());
+
+            (root_index, file.to_path_buf())
+        };
 
-        files.sort_by_key(|e| e.path().to_path_buf());
+        // A file a change creates is loaded where find_files will list it.
+        for change in changes
+        {
+            if files.contains(&change.file)
+            {
+                continue;
+            }
+
+            let change_key = sort_key(&change.file);
+            let position = files.partition_point(|file| sort_key(file) < change_key);
+            files.insert(position, change.file.clone());
+        }
 
         for file in files
         {
-            if file.path().extension().map_or(false, |e| e == "rules")
+            let (mut file_rules, mut file_errors) = match changes.iter().find(|change| change.file == file)
             {
-                let (mut file_rules, mut file_errors) = self.parse_file(file.path());
+                Some(change) => self.parse_content(&file, &change.new_content),
+                None => self.parse_file(&file),
+            };
 
-                rules.append(&mut file_rules);
-                errors.append(&mut file_errors);
-            }
+            rules.append(&mut file_rules);
+            errors.append(&mut file_errors);
         }
 
         Ok((rules, errors))
     }
 
-    pub fn save_rule(&self, rule: &AnanicyRule) -> Result<()>
+    pub fn load_cgroup_definitions(&self) -> Result<(Vec<CgroupDefinition>, Vec<Diagnostic>)>
+    {
+        self.load_definitions("cgroups", "cgroup")
+    }
+
+    pub fn load_type_definitions(&self) -> Result<(Vec<TypeDefinition>, Vec<Diagnostic>)>
+    {
+        self.load_definitions("types", "type")
+    }
+
+    fn load_definitions<T: DeserializeOwned>(&self, extension: &str, kind: &str) -> Result<(Vec<T>, Vec<Diagnostic>)>
+    {
+        let mut definitions = Vec::new();
+        let mut errors = Vec::new();
+
+        // A missing base path is already reported by load_all.
+        for file in self.find_files(extension)
+        {
+            let content = match fs::read_to_string(&file)
+            {
+                Ok(c) => c,
+
+                Err(e) =>
+                {
+                    errors.push(Diagnostic::in_file(
+                        &file,
+                        None,
+                        format!("Failed to read {} file {:?}: {}", kind, file, e),
+                    ));
+
+                    continue;
+                }
+            };
+
+            for (line_idx, line) in content.lines().enumerate()
+            {
+                let trimmed_line = line.trim();
+
+                if trimmed_line.is_empty() || trimmed_line.starts_with('#')
+                {
+                    continue;
+                }
+
+                match serde_json::from_str::<T>(trimmed_line)
+                {
+                    Ok(definition) => definitions.push(definition),
+                    Err(e) =>
+                    {
+                        errors.push(Diagnostic::in_file(
+                            &file,
+                            Some(line_idx + 1),
+                            format!("Parse error in {:?} at line {}: {}", file, line_idx + 1, e),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok((definitions, errors))
+    }
+
+    pub fn save_rule(&self, rule: &AnanicyRule) -> Result<PathBuf>
+    {
+        let change = self.plan_save_rule(rule)?;
+        self.apply_change(&change)?;
+
+        Ok(change.file)
+    }
+
+    // The rule gets a file of its own in the custom rules directory, replacing whatever that file held.
+    pub fn plan_save_rule(&self, rule: &AnanicyRule) -> Result<FileChange>
     {
         let rule_name = rule.name.as_deref().unwrap_or("unknown");
-        let file_name = format!("{}.rules", rule_name);
+        let file = self
+            .write_root()
+            .join(&self.custom_rules_dir)
+            .join(format!("{}.rules", rule_name));
 
-        let target_dir = self.base_path.join("99-custom");
-        if !target_dir.exists()
+        Ok(FileChange {
+            old_content: self.read_rule_file(&file)?,
+            new_content: serde_json::to_string(rule).context("Failed to serialize rule")?,
+            file,
+        })
+    }
+
+    pub fn apply_change(&self, change: &FileChange) -> Result<()>
+    {
+        self.apply_changes(slice::from_ref(change))
+    }
+
+    // Refuses to write when any file changed on disk after the changes were planned, before touching the others.
+    pub fn apply_changes(&self, changes: &[FileChange]) -> Result<()>
+    {
+        for change in changes
         {
-            fs::create_dir_all(&target_dir).context("Failed to create custom rules directory")?;
+            if self.read_rule_file(&change.file)? != change.old_content
+            {
+                bail!("Rule file {:?} changed on disk, review the change again", change.file);
+            }
         }
 
-        let file_path = target_dir.join(file_name);
-        let json = serde_json::to_string(rule).context("Failed to serialize rule")?;
+        for change in changes
+        {
+            if let Some(target_dir) = change.file.parent()
+                && !target_dir.exists()
+            {
+                fs::create_dir_all(target_dir).context("Failed to create rules directory")?;
+            }
 
-        fs::write(&file_path, json).context("Failed to write rule file")?;
+            fs::write(&change.file, &change.new_content)
+                .with_context(|| format!("Failed to write rule file {:?}", change.file))?;
+        }
 
         Ok(())
     }
 
-    fn parse_file(&self, path: &Path) -> (Vec<EnrichedRule>, Vec<String>)
+    fn read_rule_file(&self, file: &Path) -> Result<String>
     {
-        let mut rules = Vec::new();
-        let mut errors = Vec::new();
+        if !file.exists()
+        {
+            return Ok(String::new());
+        }
+
+        fs::read_to_string(file).with_context(|| format!("Failed to read rule file {:?}", file))
+    }
+
+    pub fn resolve_rule_file(&self, file: &Path) -> PathBuf
+    {
+        if file.is_absolute()
+        {
+            file.to_path_buf()
+        }
+        else
+        {
+            self.write_root().join(file)
+        }
+    }
 
-        let content = match fs::read_to_string(path)
+    pub fn plan_append_rule(&self, target_file: &Path, rule: &AnanicyRule) -> Result<FileChange>
+    {
+        self.plan_append_rules(target_file, &[(rule, None)])
+    }
+
+    // Appends the rules with their context comments. A commented rule starts its own block, so the comment keeps
+    // belonging to it, rules sharing a comment stay in one block.
+    pub fn plan_append_rules(&self, target_file: &Path, rules: &[(&AnanicyRule, Option<&str>)]) -> Result<FileChange>
+    {
+        let old_content = self.read_rule_file(target_file)?;
+        let mut new_content = old_content.clone();
+
+        if !new_content.is_empty() && !new_content.ends_with('\n')
         {
-            Ok(c) => c,
+            new_content.push('\n');
+        }
+
+        let mut previous_comment = None;
 
-            Err(e) =>
+        for &(rule, comment) in rules
+        {
+            if comment != previous_comment
             {
-                errors.push(format!("Failed to read rule file {:?}: {}", path, e));
+                if !new_content.is_empty() && !new_content.ends_with("\n\n")
+                {
+                    new_content.push('\n');
+                }
 
-                return (rules, errors);
+                if let Some(comment) = comment
+                {
+                    new_content.push_str(comment);
+                    new_content.push('\n');
+                }
             }
-        };
+
+            new_content.push_str(&serde_json::to_string(rule).context("Failed to serialize rule")?);
+            new_content.push('\n');
+
+            previous_comment = comment;
+        }
+
+        Ok(FileChange {
+            file: target_file.to_path_buf(),
+            old_content,
+            new_content,
+        })
+    }
+
+    // Edits the lines the rules were loaded from, all of which must be in the given file. Refuses when a line no
+    // longer holds its rule, since the file then changed after loading.
+    pub fn plan_edit_rule_lines(
+        &self,
+        source_file: &Path,
+        edits: &[(&EnrichedRule, RuleLineEdit)],
+    ) -> Result<FileChange>
+    {
+        let content =
+            fs::read_to_string(source_file).with_context(|| format!("Failed to read rule file {:?}", source_file))?;
+
+        // Each line keeps its own ending, so CRLF files and a missing final newline come out as they went in.
+        let mut lines: Vec<Option<(String, &str)>> = content
+            .split_inclusive('\n')
+            .map(|line| {
+                let body = line.trim_end_matches(['\r', '\n']);
+                Some((body.to_string(), &line[body.len()..]))
+            })
+            .collect();
+
+        for (rule, edit) in edits
+        {
+            let line_index = rule.line_number.saturating_sub(1);
+
+            let line = lines
+                .get(line_index)
+                .and_then(Option::as_ref)
+                .map(|(line, _)| line.trim())
+                .filter(|line| {
+                    line.starts_with('{')
+                        && serde_json::from_str::<AnanicyRule>(line).is_ok_and(|data| data.name == rule.data.name)
+                })
+                .with_context(|| {
+                    format!(
+                        "Line {} of {:?} no longer holds rule {:?}, reload the rules",
+                        rule.line_number,
+                        source_file,
+                        rule.data.name.as_deref().unwrap_or_default()
+                    )
+                })?;
+
+            let new_line = match edit
+            {
+                RuleLineEdit::Replace(data) => Some(serde_json::to_string(data).context("Failed to serialize rule")?),
+                RuleLineEdit::Disable => Some(format!("{}{}", DISABLED_RULE_PREFIX, line)),
+                RuleLineEdit::Remove => None,
+            };
+
+            let ending = lines[line_index].as_ref().map_or("", |(_, ending)| ending);
+            lines[line_index] = new_line.map(|new_line| (new_line, ending));
+        }
+
+        let new_content = lines
+            .into_iter()
+            .flatten()
+            .map(|(line, ending)| line + ending)
+            .collect();
+
+        Ok(FileChange {
+            file: source_file.to_path_buf(),
+            old_content: content,
+            new_content,
+        })
+    }
+
+    // Sorted within each root, roots keep their configured order.
+    fn find_files(&self, extension: &str) -> Vec<PathBuf>
+    {
+        self.base_paths
+            .iter()
+            .flat_map(|base_path| {
+                let mut files: Vec<PathBuf> = WalkDir::new(base_path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path().to_path_buf())
+                    .filter(|path| path.extension().is_some_and(|e| e == extension))
+                    .collect();
+
+                files.sort();
+                files
+            })
+            .collect()
+    }
+
+    fn parse_file(&self, path: &Path) -> (Vec<EnrichedRule>, Vec<Diagnostic>)
+    {
+        match fs::read_to_string(path)
+        {
+            Ok(content) => self.parse_content(path, &content),
+
+            Err(e) => (
+                Vec::new(),
+                vec![Diagnostic::in_file(
+                    path,
+                    None,
+                    format!("Failed to read rule file {:?}: {}", path, e),
+                )],
+            ),
+        }
+    }
+
+    fn parse_content(&self, path: &Path, content: &str) -> (Vec<EnrichedRule>, Vec<Diagnostic>)
+    {
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
 
         let mut comment_buffer = Vec::new();
         let mut rules_processed_in_block = false;
@@ -117,7 +448,12 @@ impl RuleRepository
                 continue;
             }
 
-            if trimmed_line.starts_with('#')
+            // A disabled rule still ends its comment block, but is neither a rule nor a comment.
+            if trimmed_line.starts_with(DISABLED_RULE_PREFIX.trim_end())
+            {
+                rules_processed_in_block = true;
+            }
+            else if trimmed_line.starts_with('#')
             {
                 if rules_processed_in_block
                 {
@@ -144,6 +480,7 @@ impl RuleRepository
                                 Some(comment_buffer.join("\n"))
                             },
                             source_file: path.to_path_buf(),
+                            line_number: line_idx + 1,
                             shadowed: false,
                         });
 
@@ -151,16 +488,24 @@ impl RuleRepository
                     }
                     Err(e) =>
                     {
-                        errors.push(format!("Parse error in {:?} at line {}: {}", path, line_idx + 1, e));
+                        errors.push(Diagnostic::in_file(
+                            path,
+                            Some(line_idx + 1),
+                            format!("Parse error in {:?} at line {}: {}", path, line_idx + 1, e),
+                        ));
                     }
                 }
             }
             else
             {
-                errors.push(format!(
-                    "Invalid syntax in {:?} at line {}: Line must start with '{{' or '#'",
+                errors.push(Diagnostic::in_file(
                     path,
-                    line_idx + 1
+                    Some(line_idx + 1),
+                    format!(
+                        "Invalid syntax in {:?} at line {}: Line must start with '{{' or '#'",
+                        path,
+                        line_idx + 1
+                    ),
                 ));
             }
         }
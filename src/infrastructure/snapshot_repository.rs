@@ -0,0 +1,36 @@
+use crate::domain::models::ProcessSnapshot;
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+pub struct SnapshotRepository
+{
+    file_path: PathBuf,
+}
+
+impl SnapshotRepository
+{
+    pub fn new(file_path: PathBuf) -> Self
+    {
+        Self { file_path }
+    }
+
+    pub fn load(&self) -> Result<ProcessSnapshot>
+    {
+        let content = fs::read_to_string(&self.file_path)
+            .with_context(|| format!("Failed to read snapshot file {:?}", self.file_path))?;
+
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse snapshot file {:?}", self.file_path))
+    }
+
+    pub fn save(&self, snapshot: &ProcessSnapshot) -> Result<()>
+    {
+        let json = serde_json::to_string_pretty(snapshot).context("Failed to serialize snapshot")?;
+
+        fs::write(&self.file_path, json)
+            .with_context(|| format!("Failed to write snapshot file {:?}", self.file_path))?;
+
+        Ok(())
+    }
+}
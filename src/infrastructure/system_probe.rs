@@ -0,0 +1,137 @@
+use crate::domain::models::{BlockDeviceScheduler, SystemCapabilities};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ANANICY_BINARY_DIRS: [&str; 5] = ["usr/bin", "usr/local/bin", "usr/sbin", "bin", "sbin"];
+const SYSTEMD_UNIT_DIRS: [&str; 4] = [
+    "etc/systemd/system",
+    "usr/lib/systemd/system",
+    "usr/local/lib/systemd/system",
+    "lib/systemd/system",
+];
+
+pub struct SystemProbe
+{
+    root_path: PathBuf,
+}
+
+impl Default for SystemProbe
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl SystemProbe
+{
+    pub fn new() -> Self
+    {
+        Self::new_with_root_path(PathBuf::from("/"))
+    }
+
+    pub fn new_with_root_path(root_path: PathBuf) -> Self
+    {
+        Self { root_path }
+    }
+
+    pub fn probe(&self, latency_nice: bool) -> SystemCapabilities
+    {
+        let cgroup2_mount = self.find_cgroup2_mount();
+        let cgroup2_controllers = cgroup2_mount
+            .as_ref()
+            .map(|mount| self.read_cgroup2_controllers(mount))
+            .unwrap_or_default();
+
+        SystemCapabilities {
+            latency_nice,
+            cgroup2_mount,
+            cgroup2_controllers,
+            block_devices: self.read_block_device_schedulers(),
+            autogroup_enabled: self.read_autogroup_enabled(),
+            ananicy_binary: self.find_first(&ANANICY_BINARY_DIRS, "ananicy-cpp"),
+            ananicy_service_unit: self.find_first(&SYSTEMD_UNIT_DIRS, "ananicy-cpp.service"),
+        }
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf
+    {
+        self.root_path.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    fn find_cgroup2_mount(&self) -> Option<PathBuf>
+    {
+        let mounts = fs::read_to_string(self.resolve(Path::new("/proc/mounts"))).ok()?;
+
+        mounts.lines().find_map(|line| {
+            let mount_parts: Vec<&str> = line.split_whitespace().collect();
+
+            (mount_parts.len() > 2 && mount_parts[2] == "cgroup2").then(|| PathBuf::from(mount_parts[1]))
+        })
+    }
+
+    fn read_cgroup2_controllers(&self, mount: &Path) -> Vec<String>
+    {
+        fs::read_to_string(self.resolve(mount).join("cgroup.controllers"))
+            .map(|content| content.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn read_block_device_schedulers(&self) -> Vec<BlockDeviceScheduler>
+    {
+        let Ok(block_dir) = fs::read_dir(self.resolve(Path::new("/sys/block")))
+        else
+        {
+            return Vec::new();
+        };
+
+        let mut block_devices: Vec<BlockDeviceScheduler> = block_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let device = entry.file_name().to_string_lossy().to_string();
+
+                if device.starts_with("loop") || device.starts_with("ram")
+                {
+                    return None;
+                }
+
+                let scheduler_content = fs::read_to_string(entry.path().join("queue/scheduler")).ok()?;
+
+                Some(BlockDeviceScheduler {
+                    device,
+                    scheduler: Self::parse_active_scheduler(&scheduler_content),
+                })
+            })
+            .collect();
+
+        block_devices.sort_by(|a, b| a.device.cmp(&b.device));
+        block_devices
+    }
+
+    fn parse_active_scheduler(scheduler_content: &str) -> String
+    {
+        let schedulers: Vec<&str> = scheduler_content.split_whitespace().collect();
+
+        schedulers
+            .iter()
+            .find_map(|scheduler| scheduler.strip_prefix('[').and_then(|s| s.strip_suffix(']')))
+            .or_else(|| schedulers.first().copied())
+            .unwrap_or("none")
+            .to_string()
+    }
+
+    fn read_autogroup_enabled(&self) -> Option<bool>
+    {
+        fs::read_to_string(self.resolve(Path::new("/proc/sys/kernel/sched_autogroup_enabled")))
+            .ok()
+            .map(|content| content.trim() == "1")
+    }
+
+    fn find_first(&self, dirs: &[&str], file_name: &str) -> Option<PathBuf>
+    {
+        dirs.iter()
+            .map(|dir| Path::new("/").join(dir).join(file_name))
+            .find(|path| self.resolve(path).exists())
+    }
+}
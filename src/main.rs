@@ -3,16 +3,48 @@ use clap::{Parser, Subcommand};
 
 use ananicy_rule_o_matic::application::process_service::ProcessService;
 use ananicy_rule_o_matic::application::rule_service::RuleService;
+use ananicy_rule_o_matic::domain::models::{
+    AnanicyRule, AppConfig, Diagnostic, EnrichedRule, MatchingMode, OutputFormat, Theme,
+};
+use ananicy_rule_o_matic::domain::query::SearchMode;
+use ananicy_rule_o_matic::infrastructure::config_repository::ConfigRepository;
 use ananicy_rule_o_matic::infrastructure::rule_repository::RuleRepository;
+use ananicy_rule_o_matic::infrastructure::snapshot_repository::SnapshotRepository;
 use ananicy_rule_o_matic::presentation;
 use colored::*;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args
 {
-    #[arg(short, long, default_value = "en")]
-    language: String,
+    #[arg(short, long)]
+    language: Option<String>,
+
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    // Each of these overrides the config key of the same name.
+    #[arg(long = "rule-root", global = true, value_name = "DIR")]
+    rule_roots: Vec<PathBuf>,
+
+    #[arg(long, global = true, value_name = "DIR")]
+    custom_rules_dir: Option<String>,
+
+    #[arg(long, global = true)]
+    items_per_page: Option<usize>,
+
+    #[arg(long, global = true)]
+    tick_rate_ms: Option<u64>,
+
+    #[arg(long, global = true)]
+    theme: Option<Theme>,
+
+    #[arg(long, global = true)]
+    snapshot: Option<PathBuf>,
+
+    #[arg(long, global = true)]
+    matching: Option<MatchingMode>,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -23,36 +55,348 @@ enum Commands
 {
     Search
     {
-        query: String
+        query: String,
+        #[arg(long)]
+        format: Option<OutputFormat>,
+        #[arg(long)]
+        fuzzy: bool,
+    },
+    Snapshot
+    {
+        output: PathBuf,
+    },
+    Doctor,
+    Stats,
+    List
+    {
+        #[arg(long)]
+        format: Option<OutputFormat>,
+    },
+    Status
+    {
+        #[arg(long)]
+        format: Option<OutputFormat>,
+    },
+    Show
+    {
+        name: String,
+    },
+    Add
+    {
+        #[arg(long)]
+        name: String,
+        #[arg(long = "type")]
+        rule_type: Option<String>,
+        #[arg(long, allow_hyphen_values = true)]
+        nice: Option<i32>,
+        #[arg(long, allow_hyphen_values = true)]
+        latency_nice: Option<i32>,
+        #[arg(long)]
+        sched: Option<String>,
+        #[arg(long)]
+        rtprio: Option<i32>,
+        #[arg(long)]
+        ioclass: Option<String>,
+        #[arg(long, allow_hyphen_values = true)]
+        oom_score_adj: Option<i32>,
+        #[arg(long)]
+        cgroup: Option<String>,
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    Edit
+    {
+        name: String,
+        #[arg(long = "set", value_name = "FIELD=VALUE", required = true, value_parser = parse_field_assignment)]
+        changes: Vec<(String, String)>,
+    },
+    Rm
+    {
+        name: String,
+    },
+    Mv
+    {
+        name: String,
+        #[arg(long)]
+        to: PathBuf,
     },
 }
 
-fn main() -> Result<()>
+fn parse_field_assignment(assignment: &str) -> Result<(String, String), String>
+{
+    assignment
+        .split_once('=')
+        .map(|(field, value)| (field.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("Expected FIELD=VALUE, got '{}'", assignment))
+}
+
+impl Args
+{
+    fn apply_config_overrides(&self, config: &mut AppConfig)
+    {
+        if !self.rule_roots.is_empty()
+        {
+            config.rule_roots = self.rule_roots.clone();
+        }
+
+        if let Some(custom_rules_dir) = &self.custom_rules_dir
+        {
+            config.custom_rules_dir = custom_rules_dir.clone();
+        }
+
+        if let Some(language) = &self.language
+        {
+            config.language = language.clone();
+        }
+
+        config.items_per_page = self.items_per_page.unwrap_or(config.items_per_page);
+        config.tick_rate_ms = self.tick_rate_ms.unwrap_or(config.tick_rate_ms);
+        config.theme = self.theme.unwrap_or(config.theme);
+        config.matching = self.matching.unwrap_or(config.matching);
+    }
+}
+
+impl Commands
+{
+    fn output_format(&self, default_format: OutputFormat) -> OutputFormat
+    {
+        match self
+        {
+            Commands::Search { format, .. } | Commands::List { format } | Commands::Status { format } =>
+            {
+                format.unwrap_or(default_format)
+            }
+            _ => OutputFormat::Table,
+        }
+    }
+}
+
+fn print_machine_readable(
+    format: OutputFormat,
+    rules: &[EnrichedRule],
+    errors: &[Diagnostic],
+    process_service: &ProcessService,
+) -> Result<()>
 {
-    let cli_header = format!("{}{}", "Rule-O-Matic v", env!("CARGO_PKG_VERSION"));
-    println!("{}", cli_header.cyan().bold());
+    let reports: Vec<_> = rules.iter().map(|rule| process_service.rule_report(rule)).collect();
+
+    match format
+    {
+        OutputFormat::Json => presentation::cli::print_reports_json(&reports, errors),
+        OutputFormat::Ndjson => presentation::cli::print_reports_ndjson(&reports, errors),
+        OutputFormat::Csv => presentation::cli::print_reports_csv(&reports, errors),
+        OutputFormat::Table => Ok(()),
+    }
+}
 
+fn main() -> Result<()>
+{
     let cli_args = Args::parse();
 
-    rust_i18n::set_locale(&cli_args.language);
+    let config_repository = match &cli_args.config
+    {
+        Some(config_path) => ConfigRepository::new_with_path(config_path.clone()),
+        None => ConfigRepository::new(),
+    };
+    let mut config = config_repository.load()?;
+    cli_args.apply_config_overrides(&mut config);
+    config.validate()?;
+
+    // Keep stdout clean when the output is meant for jq or a spreadsheet.
+    let machine_readable = cli_args
+        .command
+        .as_ref()
+        .is_some_and(|command| command.output_format(config.output_format).is_machine_readable());
+
+    if !machine_readable
+    {
+        let cli_header = format!("{}{}", "Rule-O-Matic v", env!("CARGO_PKG_VERSION"));
+        println!("{}", cli_header.cyan().bold());
+    }
+
+    rust_i18n::set_locale(&config.language);
 
-    let rule_repository = RuleRepository::new();
+    // --rule-root wins over ANANICY_CPP_CONFDIR, which wins over the config file.
+    let rule_repository = if cli_args.rule_roots.is_empty()
+    {
+        RuleRepository::new_with_config(&config)
+    }
+    else
+    {
+        RuleRepository::new_with_base_paths(config.rule_roots.clone(), config.custom_rules_dir.clone())
+    };
     let rule_service = RuleService::new(rule_repository);
-    let mut process_service = ProcessService::new();
+    let mut process_service = match &cli_args.snapshot
+    {
+        Some(snapshot_path) =>
+        {
+            let snapshot = SnapshotRepository::new(snapshot_path.clone()).load()?;
+
+            if !machine_readable
+            {
+                presentation::cli::print_snapshot_notice(&snapshot);
+            }
+
+            ProcessService::from_snapshot(snapshot)
+        }
+
+        None => ProcessService::new(),
+    };
+
+    process_service.set_matching_mode(config.matching);
 
     match cli_args.command
     {
-        Some(Commands::Search { query }) =>
+        Some(Commands::Search { query, format, fuzzy }) =>
+        {
+            let format = format.unwrap_or(config.output_format);
+
+            process_service.update_processes();
+
+            let search_mode = if fuzzy { SearchMode::Fuzzy } else { SearchMode::Exact };
+            let (rules, mut errors) = rule_service.search_rules(&query, search_mode, |rule_name| {
+                process_service.is_process_active(rule_name)
+            })?;
+            let (cgroup_definitions, cgroup_errors) = rule_service.load_cgroup_definitions()?;
+            errors.extend(cgroup_errors);
+
+            if format.is_machine_readable()
+            {
+                print_machine_readable(format, &rules, &errors, &process_service)?;
+            }
+            else
+            {
+                presentation::cli::print_search_results(&rules, &cgroup_definitions, &errors, &process_service);
+            }
+        }
+
+        Some(Commands::Snapshot { output }) =>
+        {
+            let snapshot = process_service.capture_snapshot();
+            SnapshotRepository::new(output.clone()).save(&snapshot)?;
+
+            presentation::cli::print_snapshot_written(&snapshot, &output);
+        }
+
+        Some(Commands::Doctor) =>
+        {
+            presentation::cli::print_doctor_report(process_service.capabilities());
+        }
+
+        Some(Commands::Stats) =>
+        {
+            process_service.update_processes();
+
+            let (rules, errors) = rule_service.load_rules()?;
+            let statistics = RuleService::compute_statistics(&rules, &errors, |rule_name| {
+                process_service.is_process_active(rule_name)
+            });
+
+            presentation::cli::print_statistics(&statistics);
+        }
+
+        Some(Commands::List { format }) =>
+        {
+            let format = format.unwrap_or(config.output_format);
+
+            process_service.update_processes();
+
+            let (rules, errors) = rule_service.load_rules()?;
+
+            if format.is_machine_readable()
+            {
+                print_machine_readable(format, &rules, &errors, &process_service)?;
+            }
+            else
+            {
+                presentation::cli::print_rule_list(&rules, &errors, &process_service);
+            }
+        }
+
+        Some(Commands::Status { format }) =>
+        {
+            let format = format.unwrap_or(config.output_format);
+
+            process_service.update_processes();
+
+            let (mut rules, mut errors) = rule_service.load_rules()?;
+            rules.retain(|rule| {
+                !rule.shadowed && process_service.is_process_active(rule.data.name.as_deref().unwrap_or_default())
+            });
+
+            if format.is_machine_readable()
+            {
+                print_machine_readable(format, &rules, &errors, &process_service)?;
+            }
+            else
+            {
+                let (cgroup_definitions, cgroup_errors) = rule_service.load_cgroup_definitions()?;
+                errors.extend(cgroup_errors);
+
+                presentation::cli::print_search_results(&rules, &cgroup_definitions, &errors, &process_service);
+            }
+        }
+
+        Some(Commands::Show { name }) =>
         {
             process_service.update_processes();
 
-            let (rules, errors) = rule_service.search_rules(&query)?;
-            presentation::cli::print_search_results(&rules, &errors, &process_service);
+            let rule = rule_service.find_rule(&name)?;
+            let (cgroup_definitions, _) = rule_service.load_cgroup_definitions()?;
+
+            presentation::cli::print_rule_details(&rule, &cgroup_definitions, &process_service);
+        }
+
+        Some(Commands::Add {
+            name,
+            rule_type,
+            nice,
+            latency_nice,
+            sched,
+            rtprio,
+            ioclass,
+            oom_score_adj,
+            cgroup,
+            file,
+        }) =>
+        {
+            let rule = AnanicyRule {
+                name: Some(name),
+                rule_type,
+                nice,
+                latency_nice,
+                sched,
+                rtprio,
+                ioclass,
+                oom_score_adj,
+                cgroup,
+            };
+
+            let file_path = rule_service.add_rule(&rule, file.as_deref())?;
+            presentation::cli::print_rule_added(rule.name.as_deref().unwrap_or_default(), &file_path);
+        }
+
+        Some(Commands::Edit { name, changes }) =>
+        {
+            let rule = rule_service.edit_rule(&name, &changes)?;
+            presentation::cli::print_rule_updated(&rule);
+        }
+
+        Some(Commands::Rm { name }) =>
+        {
+            let file_path = rule_service.remove_rule(&name)?;
+            presentation::cli::print_rule_removed(&name, &file_path);
+        }
+
+        Some(Commands::Mv { name, to }) =>
+        {
+            let file_path = rule_service.move_rule(&name, &to)?;
+            presentation::cli::print_rule_moved(&name, &file_path);
         }
 
         None =>
         {
-            presentation::tui::run_app(&rule_service, &mut process_service)?;
+            presentation::tui::run_app(&rule_service, &mut process_service, &config)?;
         }
     }
 
@@ -1,11 +1,235 @@
 use crate::application::process_service::ProcessService;
-use crate::domain::models::EnrichedRule;
+use crate::application::rule_service::RuleService;
+use crate::domain::models::{
+    AnanicyRule, AttributeReading, CgroupDefinition, CgroupResources, Diagnostic, EnrichedRule, ProcessSnapshot,
+    RuleReport, RuleStatistics, SystemCapabilities,
+};
 
+use anyhow::Result;
 use colored::*;
 use rust_i18n::t;
+use serde::Serialize;
 use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
 
-pub fn print_search_results(rules: &[EnrichedRule], errors: &[String], process_service: &ProcessService)
+pub fn print_snapshot_notice(snapshot: &ProcessSnapshot)
+{
+    let notice = t!(
+        "replaying_snapshot",
+        captured_at = snapshot.captured_at,
+        hostname = snapshot.hostname.as_deref().unwrap_or("?")
+    );
+
+    println!("{}", notice.yellow());
+}
+
+pub fn print_snapshot_written(snapshot: &ProcessSnapshot, output: &Path)
+{
+    let message = t!(
+        "snapshot_written",
+        count = snapshot.processes.len(),
+        path = output.to_string_lossy()
+    );
+
+    println!("{}", message.green());
+}
+
+pub fn print_doctor_report(capabilities: Option<&SystemCapabilities>)
+{
+    println!("{}", t!("doctor_title").bold());
+    println!();
+
+    let Some(capabilities) = capabilities
+    else
+    {
+        println!("{}", t!("doctor_no_data").yellow());
+
+        return;
+    };
+
+    if capabilities.latency_nice
+    {
+        print_doctor_line(DoctorStatus::Ok, &t!("doctor_latency_nice_ok"));
+    }
+    else
+    {
+        print_doctor_line(DoctorStatus::Missing, &t!("doctor_latency_nice_missing"));
+    }
+
+    match &capabilities.cgroup2_mount
+    {
+        Some(mount) if capabilities.supports_cgroup_cpu() => print_doctor_line(
+            DoctorStatus::Ok,
+            &t!(
+                "doctor_cgroup_ok",
+                mount = mount.to_string_lossy(),
+                controllers = capabilities.cgroup2_controllers.join(" ")
+            ),
+        ),
+        Some(mount) => print_doctor_line(
+            DoctorStatus::Missing,
+            &t!("doctor_cgroup_no_cpu", mount = mount.to_string_lossy()),
+        ),
+        None => print_doctor_line(DoctorStatus::Missing, &t!("doctor_cgroup_missing")),
+    }
+
+    if capabilities.block_devices.is_empty()
+    {
+        print_doctor_line(DoctorStatus::Warning, &t!("doctor_io_unknown"));
+    }
+
+    for block_device in &capabilities.block_devices
+    {
+        let message = t!(
+            "doctor_io_scheduler",
+            device = block_device.device,
+            scheduler = block_device.scheduler
+        );
+
+        if block_device.scheduler == "bfq" || block_device.scheduler == "mq-deadline"
+        {
+            print_doctor_line(DoctorStatus::Ok, &message);
+        }
+        else
+        {
+            print_doctor_line(
+                DoctorStatus::Warning,
+                &format!("{} {}", message, t!("doctor_io_ignored")),
+            );
+        }
+    }
+
+    match capabilities.autogroup_enabled
+    {
+        Some(true) => print_doctor_line(DoctorStatus::Warning, &t!("doctor_autogroup_enabled")),
+        Some(false) => print_doctor_line(DoctorStatus::Ok, &t!("doctor_autogroup_disabled")),
+        None => print_doctor_line(DoctorStatus::Ok, &t!("doctor_autogroup_unavailable")),
+    }
+
+    match &capabilities.ananicy_binary
+    {
+        Some(path) => print_doctor_line(
+            DoctorStatus::Ok,
+            &t!("doctor_binary_found", path = path.to_string_lossy()),
+        ),
+        None => print_doctor_line(DoctorStatus::Missing, &t!("doctor_binary_missing")),
+    }
+
+    match &capabilities.ananicy_service_unit
+    {
+        Some(path) => print_doctor_line(
+            DoctorStatus::Ok,
+            &t!("doctor_unit_found", path = path.to_string_lossy()),
+        ),
+        None => print_doctor_line(DoctorStatus::Missing, &t!("doctor_unit_missing")),
+    }
+}
+
+enum DoctorStatus
+{
+    Ok,
+    Warning,
+    Missing,
+}
+
+fn print_doctor_line(status: DoctorStatus, message: &str)
+{
+    let marker = match status
+    {
+        DoctorStatus::Ok => "✔".green().bold(),
+        DoctorStatus::Warning => "!".yellow().bold(),
+        DoctorStatus::Missing => "✘".red().bold(),
+    };
+
+    println!("  {} {}", marker, message);
+}
+
+pub fn print_statistics(statistics: &RuleStatistics)
+{
+    println!("{}", t!("stats_title").bold());
+    println!();
+    println!(
+        "  {}: {}",
+        t!("stats_total"),
+        statistics.total_rules.to_string().green()
+    );
+    println!(
+        "  {}: {}",
+        t!("stats_active"),
+        statistics.active_rules.to_string().green()
+    );
+    println!(
+        "  {}: {}",
+        t!("stats_shadowed"),
+        statistics.shadowed_rules.to_string().yellow()
+    );
+    println!(
+        "  {}: {}",
+        t!("stats_parse_errors"),
+        statistics.parse_errors.to_string().red()
+    );
+
+    print_statistics_section(&t!("stats_per_category"), &statistics.rules_per_category);
+    print_statistics_section(&t!("stats_per_type"), &statistics.rules_per_type);
+    print_statistics_section(
+        &t!("stats_per_file"),
+        statistics
+            .rules_per_file
+            .iter()
+            .map(|(path, count)| (path.to_string_lossy(), *count)),
+    );
+    print_statistics_section(&t!("stats_nice"), &statistics.nice_histogram);
+    print_statistics_section(&t!("stats_latency_nice"), &statistics.latency_nice_histogram);
+    print_statistics_section(&t!("stats_oom_score_adj"), &statistics.oom_score_adj_histogram);
+}
+
+fn print_statistics_section<K: std::fmt::Display, C: std::borrow::Borrow<usize>>(
+    title: &str,
+    counts: impl IntoIterator<Item = (K, C)>,
+)
+{
+    let counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(key, count)| (key.to_string(), *count.borrow()))
+        .collect();
+
+    println!();
+    println!("{}", title.bold());
+
+    if counts.is_empty()
+    {
+        println!("  {}", "-".dimmed());
+
+        return;
+    }
+
+    let label_width = counts.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+    let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(1);
+
+    for (key, count) in counts
+    {
+        println!(
+            "  {:>width$} {} {}",
+            key,
+            histogram_bar(count, max_count, 40).cyan(),
+            count,
+            width = label_width
+        );
+    }
+}
+
+fn histogram_bar(count: usize, max_count: usize, max_width: usize) -> String
+{
+    "█".repeat((count * max_width).div_ceil(max_count.max(1)))
+}
+
+pub fn print_search_results(
+    rules: &[EnrichedRule],
+    cgroup_definitions: &[CgroupDefinition],
+    errors: &[Diagnostic],
+    process_service: &ProcessService,
+)
 {
     if rules.is_empty()
     {
@@ -26,180 +250,430 @@ pub fn print_search_results(rules: &[EnrichedRule], errors: &[String], process_s
 
     for rule in rules
     {
-        let rule_category = rule
-            .source_file
-            .parent()
-            .and_then(|path| path.file_name())
-            .and_then(|name| name.to_str())
-            .unwrap_or("root");
+        print_rule(rule, cgroup_definitions, process_service);
+    }
+}
 
-        let rule_name = rule
-            .data
-            .name
-            .as_deref()
-            .map(Cow::Borrowed)
-            .unwrap_or_else(|| t!("unknown").into());
+pub fn print_rule_details(
+    rule: &EnrichedRule,
+    cgroup_definitions: &[CgroupDefinition],
+    process_service: &ProcessService,
+)
+{
+    print_rule(rule, cgroup_definitions, process_service);
 
-        let process_infos = process_service.get_process_infos(&rule_name);
-        let process_is_active = !process_infos.is_empty();
+    if let Ok(json) = serde_json::to_string(&rule.data)
+    {
+        println!("{}", json);
+    }
+}
 
-        let rule_display_name = if process_is_active
-        {
-            let process_id_info = process_infos
-                .first()
-                .map(|info| format!("(PID: {})", info.process_id))
-                .unwrap_or_default();
+pub fn print_rule_list(rules: &[EnrichedRule], errors: &[Diagnostic], process_service: &ProcessService)
+{
+    if !errors.is_empty()
+    {
+        eprintln!("{}", t!("error_loading_files", count = errors.len()).red());
+    }
 
-            format!("{} [ACTIVE] {}", rule_name, process_id_info).green().bold()
-        }
-        else
-        {
-            rule_name.cyan().bold()
-        };
+    for rule in rules
+    {
+        let rule_name = rule.data.name.as_deref().unwrap_or_default();
+        let mut line = format!(
+            "[{}] {} ({})",
+            rule.category().blue(),
+            rule_name.cyan().bold(),
+            rule.data.rule_type.as_deref().unwrap_or("-")
+        );
 
-        let shadowed_marker = if rule.shadowed
+        if rule.shadowed
         {
-            format!(" {}", "(Shadowed)".red())
+            line.push_str(&format!(" {}", "(Shadowed)".red()));
         }
-        else
+        else if process_service.is_process_active(rule_name)
         {
-            String::new()
-        };
-
-        print!(
-            "[{}] Name: {}{}",
-            rule_category.blue(),
-            rule_display_name,
-            shadowed_marker
+            line.push_str(&format!(" {}", "[ACTIVE]".green().bold()));
+        }
+
+        println!("{}", line);
+    }
+}
+
+#[derive(Serialize)]
+struct ReportDocument<'a>
+{
+    rules: &'a [RuleReport],
+    errors: &'a [Diagnostic],
+}
+
+pub fn print_reports_json(reports: &[RuleReport], errors: &[Diagnostic]) -> Result<()>
+{
+    let document = ReportDocument { rules: reports, errors };
+    let mut stdout = io::stdout().lock();
+
+    serde_json::to_writer_pretty(&mut stdout, &document)?;
+    writeln!(stdout)?;
+
+    Ok(())
+}
+
+// One report per line. Loading errors go to stderr so every stdout line stays a rule.
+pub fn print_reports_ndjson(reports: &[RuleReport], errors: &[Diagnostic]) -> Result<()>
+{
+    let mut stdout = io::stdout().lock();
+
+    for report in reports
+    {
+        serde_json::to_writer(&mut stdout, report)?;
+        writeln!(stdout)?;
+    }
+
+    print_errors_to_stderr(errors);
+
+    Ok(())
+}
+
+pub fn print_reports_csv(reports: &[RuleReport], errors: &[Diagnostic]) -> Result<()>
+{
+    let mut stdout = io::stdout().lock();
+
+    let mut header = vec!["category"];
+    header.extend(AnanicyRule::FIELDS);
+    header.extend(["source_file", "shadowed", "pids", "mismatches"]);
+    writeln!(stdout, "{}", header.join(","))?;
+
+    for report in reports
+    {
+        let mut row = vec![report.category.clone()];
+        row.extend(
+            AnanicyRule::FIELDS
+                .iter()
+                .map(|field| report.rule.get_field(field).unwrap_or_default()),
         );
 
-        if let Some(rule_type) = rule.data.rule_type.as_deref()
+        let process_ids: Vec<String> = report
+            .processes
+            .iter()
+            .map(|process| process.info.process_id.to_string())
+            .collect();
+        let mut mismatches: Vec<&str> = report
+            .processes
+            .iter()
+            .flat_map(|process| process.mismatches.iter().map(String::as_str))
+            .collect();
+        mismatches.sort_unstable();
+        mismatches.dedup();
+
+        row.push(report.source_file.to_string_lossy().into_owned());
+        row.push(report.shadowed.to_string());
+        row.push(process_ids.join(" "));
+        row.push(mismatches.join(" "));
+
+        let row: Vec<Cow<str>> = row.iter().map(|value| escape_csv(value)).collect();
+        writeln!(stdout, "{}", row.join(","))?;
+    }
+
+    print_errors_to_stderr(errors);
+
+    Ok(())
+}
+
+fn escape_csv(value: &str) -> Cow<'_, str>
+{
+    if value.contains([',', '"', '\n', '\r'])
+    {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    }
+    else
+    {
+        Cow::Borrowed(value)
+    }
+}
+
+fn print_errors_to_stderr(errors: &[Diagnostic])
+{
+    for error in errors
+    {
+        eprintln!("{}", error);
+    }
+}
+
+pub fn print_rule_added(rule_name: &str, file_path: &Path)
+{
+    println!(
+        "{}",
+        t!("rule_added", name = rule_name, path = file_path.to_string_lossy()).green()
+    );
+}
+
+pub fn print_rule_updated(rule: &EnrichedRule)
+{
+    println!(
+        "{}",
+        t!(
+            "rule_updated",
+            name = rule.data.name.as_deref().unwrap_or_default(),
+            path = rule.source_file.to_string_lossy()
+        )
+        .green()
+    );
+}
+
+pub fn print_rule_removed(rule_name: &str, file_path: &Path)
+{
+    println!(
+        "{}",
+        t!("rule_removed", name = rule_name, path = file_path.to_string_lossy()).green()
+    );
+}
+
+pub fn print_rule_moved(rule_name: &str, file_path: &Path)
+{
+    println!(
+        "{}",
+        t!("rule_moved", name = rule_name, path = file_path.to_string_lossy()).green()
+    );
+}
+
+fn print_rule(rule: &EnrichedRule, cgroup_definitions: &[CgroupDefinition], process_service: &ProcessService)
+{
+    let rule_category = rule.category();
+
+    let rule_name = rule
+        .data
+        .name
+        .as_deref()
+        .map(Cow::Borrowed)
+        .unwrap_or_else(|| t!("unknown"));
+
+    let process_infos = process_service.get_process_infos(&rule_name);
+    let process_is_active = !process_infos.is_empty();
+
+    let rule_display_name = if process_is_active
+    {
+        let process_id_info = process_infos
+            .first()
+            .map(|info| format!("(PID: {})", info.process_id))
+            .unwrap_or_default();
+
+        format!("{} [ACTIVE] {}", rule_name, process_id_info).green().bold()
+    }
+    else
+    {
+        rule_name.cyan().bold()
+    };
+
+    let shadowed_marker = if rule.shadowed
+    {
+        format!(" {}", "(Shadowed)".red())
+    }
+    else
+    {
+        String::new()
+    };
+
+    let match_marker = match process_infos
+        .first()
+        .and_then(|info| process_service.explain_match(&rule_name, info.process_id))
+    {
+        Some(match_kind) if match_kind.is_weak() => format!(" ({})", t!("matched_by", kind = match_kind))
+            .yellow()
+            .to_string(),
+        Some(match_kind) => format!(" ({})", t!("matched_by", kind = match_kind))
+            .dimmed()
+            .to_string(),
+        None => String::new(),
+    };
+
+    print!(
+        "[{}] Name: {}{}{}",
+        rule_category.blue(),
+        rule_display_name,
+        match_marker,
+        shadowed_marker
+    );
+
+    if let Some(rule_type) = rule.data.rule_type.as_deref()
+    {
+        print!(" | Type: {}", rule_type.white());
+    }
+
+    if let Some(nice) = rule.data.nice
+    {
+        print!(" | Nice: {}", nice.to_string().yellow());
+    }
+
+    if let Some(latency_nice) = rule.data.latency_nice
+    {
+        print!(" | Latency: {}", latency_nice.to_string().magenta());
+    }
+
+    if let Some(sched) = rule.data.sched.as_deref()
+    {
+        print!(" | Sched: {}", sched);
+    }
+
+    if let Some(ioclass) = rule.data.ioclass.as_deref()
+    {
+        print!(" | IO: {}", ioclass);
+    }
+
+    if let Some(rtprio) = &rule.data.rtprio
+    {
+        print!(" | Static priority: {}", rtprio);
+    }
+
+    if let Some(oom_score_adj) = &rule.data.oom_score_adj
+    {
+        print!(" | Out of memory killer score: {}", oom_score_adj);
+    }
+
+    if let Some(cgroup) = &rule.data.cgroup
+    {
+        print!(" | Cgroup: {}", ProcessService::shorten_cgroup(cgroup));
+    }
+
+    println!();
+
+    if process_is_active
+    {
+        let process_info = &process_infos[0];
+        let capabilities = process_service.capabilities();
+        let mut status_parts = Vec::new();
+
+        if rule.data.nice.is_some() || process_info.nice.is_value()
         {
-            print!(" | Type: {}", rule_type.white());
+            status_parts.push(format_status_check_i32("Nice", rule.data.nice, &process_info.nice));
         }
 
-        if let Some(nice) = rule.data.nice
+        if let AttributeReading::Value(autogroup) = &process_info.autogroup
         {
-            print!(" | Nice: {}", nice.to_string().yellow());
+            status_parts.push(format!("Autogroup: {} (nice {})", autogroup.id, autogroup.nice).normal());
         }
 
-        if let Some(latency_nice) = rule.data.latency_nice
+        if rule.data.latency_nice.is_some() || process_info.latency_nice.is_value()
         {
-            print!(" | Latency: {}", latency_nice.to_string().magenta());
+            status_parts.push(mark_if_unsupported(
+                format_status_check_i32("LatNice", rule.data.latency_nice, &process_info.latency_nice),
+                capabilities.is_none_or(|c| c.latency_nice),
+            ));
         }
 
-        if let Some(sched) = rule.data.sched.as_deref()
+        if rule.data.sched.is_some() || process_info.sched_policy.is_value()
         {
-            print!(" | Sched: {}", sched);
+            status_parts.push(format_status_check_str(
+                "Sched",
+                &rule.data.sched,
+                &process_info.sched_policy,
+            ));
         }
 
-        if let Some(ioclass) = rule.data.ioclass.as_deref()
+        if rule.data.ioclass.is_some() || process_info.ioclass.is_value()
         {
-            print!(" | IO: {}", ioclass);
+            status_parts.push(mark_if_unsupported(
+                format_status_check_str("IO", &rule.data.ioclass, &process_info.ioclass),
+                capabilities.is_none_or(|c| c.supports_ioclass()),
+            ));
         }
 
-        if let Some(rtprio) = &rule.data.rtprio
+        if rule.data.oom_score_adj.is_some() || process_info.oom_score_adj.is_value()
         {
-            print!(" | Static priority: {}", rtprio);
+            status_parts.push(format_status_check_i32(
+                "OOM",
+                rule.data.oom_score_adj,
+                &process_info.oom_score_adj,
+            ));
         }
 
-        if let Some(oom_score_adj) = &rule.data.oom_score_adj
+        if rule.data.cgroup.is_some() || process_info.cgroup.is_value()
         {
-            print!(" | Out of memory killer score: {}", oom_score_adj);
+            status_parts.push(mark_if_unsupported(
+                format_status_check_cgroup("Cgroup", &rule.data.cgroup, &process_info.cgroup),
+                capabilities.is_none_or(|c| c.supports_cgroup_cpu()),
+            ));
         }
 
-        if let Some(cgroup) = &rule.data.cgroup
+        let cpu_quota = rule
+            .data
+            .cgroup
+            .as_deref()
+            .and_then(|cgroup_name| RuleService::find_cgroup_definition(cgroup_definitions, cgroup_name))
+            .and_then(|definition| definition.cpu_quota);
+
+        if let Some(cpu_quota) = cpu_quota
+            && let Some(cgroup_resources) = process_service.get_cgroup_resources(process_info)
         {
-            print!(" | Cgroup: {}", ProcessService::shorten_cgroup(cgroup));
+            status_parts.push(format_status_check_cpu_quota(cpu_quota, &cgroup_resources));
         }
 
-        println!();
+        let status_line: Vec<String> = status_parts
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| part.to_string())
+            .collect();
 
-        if process_is_active
+        if !status_line.is_empty()
         {
-            let process_info = &process_infos[0];
-            let mut status_parts = Vec::new();
-
-            if rule.data.nice.is_some() || process_info.nice.is_some()
-            {
-                status_parts.push(format_status_check_i32("Nice", rule.data.nice, process_info.nice));
-            }
+            println!("  ↳ Status: {}", status_line.join(" | "));
+        }
 
-            if rule.data.latency_nice.is_some() || process_info.latency_nice.is_some()
-            {
-                status_parts.push(format_status_check_i32(
-                    "LatNice",
-                    rule.data.latency_nice,
-                    process_info.latency_nice,
-                ));
-            }
+        if process_service.autogroup_overrides_nice(rule.data.nice, process_info)
+        {
+            println!("  ⚠ {}", t!("autogroup_nice_warning").yellow());
+        }
+    }
 
-            if rule.data.sched.is_some() || process_info.sched_policy.is_some()
-            {
-                status_parts.push(format_status_check_str(
-                    "Sched",
-                    &rule.data.sched,
-                    &process_info.sched_policy,
-                ));
-            }
+    println!(
+        "  {}: {}",
+        t!("file"),
+        format!("{}:{}", rule.source_file.display(), rule.line_number).dimmed()
+    );
 
-            if rule.data.ioclass.is_some() || process_info.ioclass.is_some()
-            {
-                status_parts.push(format_status_check_str("IO", &rule.data.ioclass, &process_info.ioclass));
-            }
+    if let Some(comment) = &rule.context_comment
+    {
+        println!("  {}:", t!("info"));
+        for line in comment.lines()
+        {
+            println!("    {}", line.italic().dimmed());
+        }
+    }
 
-            if rule.data.oom_score_adj.is_some() || process_info.oom_score_adj.is_some()
-            {
-                status_parts.push(format_status_check_i32(
-                    "OOM",
-                    rule.data.oom_score_adj,
-                    process_info.oom_score_adj,
-                ));
-            }
+    println!();
+}
 
-            if rule.data.cgroup.is_some() || process_info.cgroup.is_some()
-            {
-                status_parts.push(format_status_check_cgroup(
-                    "Cgroup",
-                    &rule.data.cgroup,
-                    &process_info.cgroup,
-                ));
-            }
+fn mark_if_unsupported(status_part: ColoredString, supported: bool) -> ColoredString
+{
+    if supported || status_part.is_empty()
+    {
+        return status_part;
+    }
 
-            let status_line: Vec<String> = status_parts
-                .into_iter()
-                .filter(|part| !part.is_empty())
-                .map(|part| part.to_string())
-                .collect();
+    format!("{} [{}]", status_part.clear(), t!("not_supported_here")).dimmed()
+}
 
-            if !status_line.is_empty()
-            {
-                println!("  ↳ Status: {}", status_line.join(" | "));
-            }
+fn format_status_check_cpu_quota(cpu_quota: u32, cgroup_resources: &CgroupResources) -> ColoredString
+{
+    match (&cgroup_resources.cpu_max, cgroup_resources.cpu_quota_percent())
+    {
+        (AttributeReading::Value(_), Some(_)) if cgroup_resources.enforces_cpu_quota(cpu_quota) =>
+        {
+            "CPUQuota ok".green()
         }
-
-        println!("  {}: {}", t!("file"), rule.source_file.to_string_lossy().dimmed());
-
-        if let Some(comment) = &rule.context_comment
+        (AttributeReading::Value(_), Some(percent)) =>
         {
-            println!("  {}:", t!("info"));
-            for line in comment.lines()
-            {
-                println!("    {}", line.italic().dimmed());
-            }
+            format!("CPUQuota {:.0}%! (want {}%)", percent, cpu_quota).red().bold()
         }
-
-        println!();
+        (AttributeReading::Value(_), None) => format!("CPUQuota max! (want {}%)", cpu_quota).red().bold(),
+        (reading, _) => format_status_unreadable("CPUQuota", Some(format!("{}%", cpu_quota)), reading),
     }
 }
 
-fn format_status_check_i32(label: &str, want: Option<i32>, have: Option<i32>) -> ColoredString
+fn format_status_check_i32(label: &str, want: Option<i32>, have: &AttributeReading<i32>) -> ColoredString
 {
     match (want, have)
     {
-        (Some(wanted), Some(had)) =>
+        (Some(wanted), AttributeReading::Value(had)) =>
         {
-            if wanted == had
+            if wanted == *had
             {
                 format!("{} ok", label).green()
             }
@@ -208,17 +682,16 @@ fn format_status_check_i32(label: &str, want: Option<i32>, have: Option<i32>) ->
                 format!("{} {}! (want {})", label, had, wanted).red().bold()
             }
         }
-        (None, Some(had)) => format!("{}: {}", label, had).dimmed(),
-        (Some(wanted), None) => format!("{}: ? (want {})", label, wanted).red(),
-        (None, None) => "".dimmed(),
+        (None, AttributeReading::Value(had)) => format!("{}: {}", label, had).dimmed(),
+        (want, reading) => format_status_unreadable(label, want.map(|wanted| wanted.to_string()), reading),
     }
 }
 
-fn format_status_check_str(label: &str, want: &Option<String>, have: &Option<String>) -> ColoredString
+fn format_status_check_str(label: &str, want: &Option<String>, have: &AttributeReading<String>) -> ColoredString
 {
     match (want, have)
     {
-        (Some(wanted), Some(had)) =>
+        (Some(wanted), AttributeReading::Value(had)) =>
         {
             if wanted.eq_ignore_ascii_case(had)
             {
@@ -229,17 +702,16 @@ fn format_status_check_str(label: &str, want: &Option<String>, have: &Option<Str
                 format!("{} {}! (want {})", label, had, wanted).red().bold()
             }
         }
-        (None, Some(had)) => format!("{}: {}", label, had).dimmed(),
-        (Some(wanted), None) => format!("{}: ? (want {})", label, wanted).red(),
-        (None, None) => "".dimmed(),
+        (None, AttributeReading::Value(had)) => format!("{}: {}", label, had).dimmed(),
+        (want, reading) => format_status_unreadable(label, want.clone(), reading),
     }
 }
 
-fn format_status_check_cgroup(label: &str, want: &Option<String>, have: &Option<String>) -> ColoredString
+fn format_status_check_cgroup(label: &str, want: &Option<String>, have: &AttributeReading<String>) -> ColoredString
 {
     match (want, have)
     {
-        (Some(wanted), Some(had)) =>
+        (Some(wanted), AttributeReading::Value(had)) =>
         {
             let short_had = ProcessService::shorten_cgroup(had);
             if wanted.eq_ignore_ascii_case(had)
@@ -252,16 +724,33 @@ fn format_status_check_cgroup(label: &str, want: &Option<String>, have: &Option<
                 format!("{} {}! (want {})", label, short_had, short_wanted).red().bold()
             }
         }
-        (None, Some(had)) =>
+        (None, AttributeReading::Value(had)) =>
         {
             let short_had = ProcessService::shorten_cgroup(had);
             format!("{}: {}", label, short_had).dimmed()
         }
-        (Some(wanted), None) =>
-        {
-            let short_wanted = ProcessService::shorten_cgroup(wanted);
-            format!("{}: ? (want {})", label, short_wanted).red()
-        }
-        (None, None) => "".dimmed(),
+        (want, reading) => format_status_unreadable(
+            label,
+            want.as_deref()
+                .map(|wanted| ProcessService::shorten_cgroup(wanted).to_string()),
+            reading,
+        ),
+    }
+}
+
+fn format_status_unreadable<T>(label: &str, want: Option<String>, have: &AttributeReading<T>) -> ColoredString
+{
+    let Some(wanted) = want
+    else
+    {
+        return "".dimmed();
+    };
+
+    match have
+    {
+        AttributeReading::Value(_) => "".dimmed(),
+        AttributeReading::Unsupported => format!("{}: unsupported (want {})", label, wanted).yellow(),
+        AttributeReading::PermissionDenied => format!("{}: permission denied (want {})", label, wanted).yellow(),
+        AttributeReading::Gone => format!("{}: process gone (want {})", label, wanted).dimmed(),
     }
 }
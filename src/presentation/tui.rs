@@ -1,6 +1,14 @@
 use crate::application::process_service::ProcessService;
+use crate::application::refresh_worker::{RefreshWorker, WorkerEvent};
 use crate::application::rule_service::RuleService;
-use crate::domain::models::{AnanicyRule, EnrichedRule};
+use crate::domain::models::{
+    AnanicyRule, AppConfig, AttributeReading, BulkAction, CgroupDefinition, Diagnostic, EnrichedRule, FileChange,
+    MatchKind, MatchingMode, ProcessAttributes, ProcessTreeNode, RuleColumn, RuleSort, RuleStatistics, RuleTreeNode,
+    RuleTreeNodeKind, SavePlan, Theme, TypeDefinition,
+};
+use crate::domain::pagination::Pagination;
+use crate::domain::query::{Query, SearchMode, Term};
+use crate::infrastructure::editor_launcher::EditorLauncher;
 
 use anyhow::Result;
 
@@ -13,35 +21,64 @@ use crossterm::{
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap},
 };
 
 use rust_i18n::t;
 use std::{
     borrow::Cow,
+    collections::{HashMap, HashSet},
     io,
+    path::{Path, PathBuf},
+    thread,
     time::{Duration, Instant},
 };
 
+// The rule form edits the fields of AnanicyRule::FIELDS followed by the file the rule is saved to.
+const FORM_FIELD_COUNT: usize = 10;
+const TYPE_FIELD: usize = 1;
+const SCHED_FIELD: usize = 4;
+const IOCLASS_FIELD: usize = 6;
+const CGROUP_FIELD: usize = 8;
+const TARGET_FILE_FIELD: usize = 9;
+
+// Locale keys of the bulk actions in menu order, the first two ask for a value before the preview.
+const BULK_ACTIONS: [&str; 4] = ["bulk_set_field", "bulk_move", "bulk_disable", "bulk_delete"];
+const BULK_SET_FIELD: usize = 0;
+const BULK_MOVE: usize = 1;
+const BULK_DISABLE: usize = 2;
+
 struct App
 {
     all_rules: Vec<EnrichedRule>,
-    rules_errors: Vec<String>,
-    current_page: usize,
+    cgroup_definitions: Vec<CgroupDefinition>,
+    type_definitions: Vec<TypeDefinition>,
+    rules_errors: Vec<Diagnostic>,
     filter_active_only: bool,
     filtered_rules: Vec<EnrichedRule>,
     input_mode: InputMode,
-    items_per_page: usize,
-    list_state: ListState,
+    // Pages hold the rows that fit the rules table, at most max_items_per_page.
+    pagination: Pagination,
+    max_items_per_page: usize,
+    list_state: TableState,
     search_query: String,
+    search: Query,
+    search_mode: SearchMode,
+    search_error: Option<String>,
 
     // Editing fields
     editing_rule: AnanicyRule,
+    // The rule as loaded when editing an existing one, its line is the one the save rewrites.
+    editing_original: Option<EnrichedRule>,
     editing_field_index: usize,
     editing_buffer: String,
+    // The text of every form field, the last one is the target file. An empty target file means the rule gets
+    // a file of its own in the custom rules directory.
+    editing_values: Vec<String>,
     notification: Option<(String, Color)>,
     notification_time: Option<Instant>,
 
@@ -49,8 +86,73 @@ struct App
     process_list: Vec<String>,
     process_list_state: ListState,
 
+    // Save preview
+    pending_save: Option<SavePlan>,
+    preview_scroll: u16,
+
+    // Multi-selection by file and rule name, so marks stay on their rules when lines shift
+    marked_rules: HashSet<(PathBuf, String)>,
+    bulk_menu_state: ListState,
+    bulk_input: String,
+
+    // Diagnostics, failed saves are kept next to the loading problems
+    save_errors: Vec<Diagnostic>,
+    diagnostics_state: ListState,
+
+    // Picker choices for the selected form field
+    field_options: Vec<String>,
+    field_options_state: ListState,
+
     // Permissions
     is_root: bool,
+
+    // Background refresh
+    loading_rules: bool,
+    scanning_processes: bool,
+    // Selected again by file and line once the rules reload after editing them
+    reselect_rule: Option<(PathBuf, usize)>,
+
+    theme: Theme,
+    editor_line_arg: Option<String>,
+
+    // Rules table, without a sort column the rules keep their ranking
+    rule_sort: Option<RuleSort>,
+    jump_prefix: String,
+    hidden_columns: HashSet<RuleColumn>,
+    column_picker_state: ListState,
+
+    // Rule tree sidebar, the first entry lists all rules
+    rule_roots: Vec<PathBuf>,
+    rule_tree: Vec<RuleTreeNode>,
+    collapsed_nodes: HashSet<PathBuf>,
+    sidebar_state: ListState,
+    sidebar_focused: bool,
+    tree_filter: Option<PathBuf>,
+
+    // Process tree
+    view: View,
+    tree_state: ListState,
+    tree_len: usize,
+    tree_matched_only: bool,
+
+    // Derived from the last scan and rule load, drawing only reads them
+    process_tree: Vec<ProcessTreeNode>,
+    rule_matches: HashMap<i32, (EnrichedRule, MatchKind)>,
+    inherited_rules: HashMap<i32, EnrichedRule>,
+    has_matched_descendant: HashSet<i32>,
+    statistics: RuleStatistics,
+    // Processes the worker reads attributes for: matched ones and the tree rows on screen
+    visible_tree_processes: Vec<i32>,
+    watched_processes: HashSet<i32>,
+}
+
+#[derive(PartialEq)]
+enum View
+{
+    Rules,
+    ProcessTree,
+    Dashboard,
+    Diagnostics,
 }
 
 #[derive(PartialEq)]
@@ -58,33 +160,87 @@ enum InputMode
 {
     Editing, // Searching
     Normal,
-    RuleForm, // Creating/Editing Rule
+    RuleForm,     // Creating/Editing Rule
+    ConfirmSave,  // Reviewing the diff of a rule about to be saved
+    ColumnPicker, // Showing or hiding columns of the rules table
+    Jump,         // Typing the first letters of a rule name
+    BulkMenu,     // Choosing what to do with the marked rules
+    BulkInput,    // Typing the value a bulk action needs
+    ConfirmBulk,  // Reviewing the diff of a bulk action
 }
 
 impl App
 {
-    fn new(rules: Vec<EnrichedRule>, errors: Vec<String>) -> Self
+    fn new(rules: Vec<EnrichedRule>, errors: Vec<Diagnostic>) -> Self
     {
         let is_root = unsafe { libc::geteuid() == 0 };
 
         let mut app = Self {
             all_rules: rules.clone(),
+            cgroup_definitions: Vec::new(),
+            type_definitions: Vec::new(),
             rules_errors: errors,
             filtered_rules: rules,
             filter_active_only: false,
-            list_state: ListState::default(),
+            list_state: TableState::default(),
             search_query: String::new(),
+            search: Query::All,
+            search_mode: SearchMode::Exact,
+            search_error: None,
             input_mode: InputMode::Normal,
-            current_page: 0,
-            items_per_page: 50,
+            pagination: Pagination::new(50),
+            max_items_per_page: 50,
             editing_rule: AnanicyRule::default(),
+            editing_original: None,
             editing_field_index: 0,
             editing_buffer: String::new(),
+            editing_values: vec![String::new(); FORM_FIELD_COUNT],
             notification: None,
             notification_time: None,
             process_list: Vec::new(),
             process_list_state: ListState::default(),
+            pending_save: None,
+            preview_scroll: 0,
+            marked_rules: HashSet::new(),
+            bulk_menu_state: ListState::default(),
+            bulk_input: String::new(),
+            save_errors: Vec::new(),
+            diagnostics_state: ListState::default(),
+            field_options: Vec::new(),
+            field_options_state: ListState::default(),
             is_root,
+            loading_rules: false,
+            scanning_processes: false,
+            reselect_rule: None,
+            theme: Theme::default(),
+            editor_line_arg: None,
+            rule_sort: None,
+            jump_prefix: String::new(),
+            hidden_columns: HashSet::from([
+                RuleColumn::LatencyNice,
+                RuleColumn::Sched,
+                RuleColumn::IoClass,
+                RuleColumn::OomScoreAdj,
+                RuleColumn::Cgroup,
+            ]),
+            column_picker_state: ListState::default(),
+            rule_roots: Vec::new(),
+            rule_tree: Vec::new(),
+            collapsed_nodes: HashSet::new(),
+            sidebar_state: ListState::default(),
+            sidebar_focused: false,
+            tree_filter: None,
+            view: View::Rules,
+            tree_state: ListState::default(),
+            tree_len: 0,
+            tree_matched_only: false,
+            process_tree: Vec::new(),
+            rule_matches: HashMap::new(),
+            inherited_rules: HashMap::new(),
+            has_matched_descendant: HashSet::new(),
+            statistics: RuleStatistics::default(),
+            visible_tree_processes: Vec::new(),
+            watched_processes: HashSet::new(),
         };
 
         if !app.filtered_rules.is_empty()
@@ -95,175 +251,574 @@ impl App
         app
     }
 
-    fn next(&mut self)
+    // The position of the selected rule in filtered_rules, across pages.
+    fn selected_index(&self) -> Option<usize>
+    {
+        self.list_state
+            .selected()
+            .map(|selected| self.pagination.index_of(selected))
+    }
+
+    // Turns to the page holding the rule, so scrolling runs on across page boundaries.
+    fn select_index(&mut self, index: usize)
+    {
+        let row = self.pagination.select(index, self.filtered_rules.len());
+        self.list_state.select(row);
+    }
+
+    // The page size follows the height of the rules table, capped by items_per_page from the config.
+    fn resize_page(&mut self, table_height: u16)
+    {
+        let items_per_page = Pagination::rows_for_height(table_height, self.max_items_per_page);
+        let row = self
+            .pagination
+            .resize(items_per_page, self.list_state.selected(), self.filtered_rules.len());
+        self.list_state.select(row);
+    }
+
+    fn scroll_by(&mut self, step: isize)
+    {
+        let index = self
+            .selected_index()
+            .map_or(0, |index| index.saturating_add_signed(step));
+        self.select_index(index);
+    }
+
+    fn next_page(&mut self)
+    {
+        if self.pagination.next_page(self.filtered_rules.len())
+        {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    // Selects the first rule whose name starts with the typed letters, ignoring case.
+    fn jump_to_prefix(&mut self)
+    {
+        let prefix = self.jump_prefix.to_lowercase();
+
+        let position = self.filtered_rules.iter().position(|rule| {
+            rule.data
+                .name
+                .as_deref()
+                .is_some_and(|name| name.to_lowercase().starts_with(&prefix))
+        });
+
+        if let Some(position) = position
+        {
+            self.select_index(position);
+        }
+    }
+
+    fn next_tree_node(&mut self)
+    {
+        let selected = self.tree_state.selected().map_or(0, |selected| selected + 1);
+        self.tree_state
+            .select(Some(selected.min(self.tree_len.saturating_sub(1))));
+    }
+
+    fn previous_tree_node(&mut self)
+    {
+        let selected = self.tree_state.selected().unwrap_or(0);
+        self.tree_state.select(Some(selected.saturating_sub(1)));
+    }
+
+    fn selected_rule(&self) -> Option<&EnrichedRule>
+    {
+        self.filtered_rules.get(self.selected_index()?)
+    }
+
+    fn visible_columns(&self) -> Vec<RuleColumn>
+    {
+        RuleColumn::ALL
+            .into_iter()
+            .filter(|column| !self.hidden_columns.contains(column))
+            .collect()
+    }
+
+    // Steps through the visible columns, with the unsorted ranking between the last and the first.
+    fn cycle_sort_column(&mut self, step: isize, process_service: &ProcessService)
+    {
+        let mut choices: Vec<Option<RuleColumn>> = vec![None];
+        choices.extend(self.visible_columns().into_iter().map(Some));
+
+        let current = choices
+            .iter()
+            .position(|column| *column == self.rule_sort.map(|rule_sort| rule_sort.column))
+            .unwrap_or(0);
+        let next = (current as isize + step).rem_euclid(choices.len() as isize) as usize;
+
+        self.rule_sort = choices[next].map(|column| RuleSort {
+            column,
+            descending: false,
+        });
+        self.update_search(process_service);
+    }
+
+    fn reverse_sort(&mut self, process_service: &ProcessService)
+    {
+        if let Some(rule_sort) = &mut self.rule_sort
+        {
+            rule_sort.descending = !rule_sort.descending;
+            self.update_search(process_service);
+        }
+    }
+
+    // Hiding the sort column falls back to the ranking, the last visible column stays.
+    fn toggle_selected_column(&mut self, process_service: &ProcessService)
+    {
+        let Some(column) = self
+            .column_picker_state
+            .selected()
+            .and_then(|selected| RuleColumn::ALL.get(selected).copied())
+        else
+        {
+            return;
+        };
+
+        if self.hidden_columns.remove(&column) || self.visible_columns().len() == 1
+        {
+            return;
+        }
+
+        self.hidden_columns.insert(column);
+
+        if self.rule_sort.is_some_and(|rule_sort| rule_sort.column == column)
+        {
+            self.rule_sort = None;
+            self.update_search(process_service);
+        }
+    }
+
+    fn is_marked(&self, rule: &EnrichedRule) -> bool
+    {
+        self.marked_rules.contains(&RuleService::mark_key(rule))
+    }
+
+    fn toggle_selected_mark(&mut self)
     {
-        if let Some(selected) = self.list_state.selected()
+        if let Some(rule) = self.selected_rule()
         {
-            let start = self.current_page * self.items_per_page;
-            let end = (start + self.items_per_page).min(self.filtered_rules.len());
-            let count_on_page = end - start;
+            let key = RuleService::mark_key(rule);
 
-            if count_on_page > 0 && selected < count_on_page - 1
+            if !self.marked_rules.remove(&key)
             {
-                self.list_state.select(Some(selected + 1));
+                self.marked_rules.insert(key);
             }
+
+            self.scroll_by(1);
         }
     }
 
-    fn next_page(&mut self)
+    // Marks every rule the list shows, or unmarks them when they all are marked already.
+    fn toggle_all_marks(&mut self)
     {
-        let total_pages = (self.filtered_rules.len() as f64 / self.items_per_page as f64).ceil() as usize;
+        let keys: Vec<(PathBuf, String)> = self.filtered_rules.iter().map(RuleService::mark_key).collect();
 
-        if total_pages > 0 && self.current_page < total_pages - 1
+        if keys.iter().all(|key| self.marked_rules.contains(key))
         {
-            self.current_page += 1;
-            self.list_state.select(Some(0));
+            for key in &keys
+            {
+                self.marked_rules.remove(key);
+            }
+        }
+        else
+        {
+            self.marked_rules.extend(keys);
         }
     }
 
-    fn previous(&mut self)
+    fn marked_rule_list(&self) -> Vec<EnrichedRule>
+    {
+        RuleService::marked_rules(&self.all_rules, &self.marked_rules)
+    }
+
+    fn plan_bulk_action(&mut self, rule_service: &RuleService, action: BulkAction)
     {
-        if let Some(selected) = self.list_state.selected()
+        match rule_service.plan_bulk_action(&self.marked_rule_list(), &action)
         {
-            if selected > 0
+            Ok(save_plan) =>
+            {
+                self.pending_save = Some(save_plan);
+                self.preview_scroll = 0;
+                self.input_mode = InputMode::ConfirmBulk;
+            }
+            Err(e) =>
             {
-                self.list_state.select(Some(selected - 1));
+                self.notification = Some((format!("Error: {:#}", e), Color::Red));
+                self.notification_time = Some(Instant::now());
             }
         }
     }
 
+    fn is_sidebar_active(&self) -> bool
+    {
+        self.sidebar_focused && self.view == View::Rules
+    }
+
+    // Nodes below a collapsed node are hidden.
+    fn visible_tree_nodes(&self) -> Vec<&RuleTreeNode>
+    {
+        self.rule_tree
+            .iter()
+            .filter(|node| {
+                !self
+                    .collapsed_nodes
+                    .iter()
+                    .any(|collapsed| node.path != *collapsed && node.path.starts_with(collapsed))
+            })
+            .collect()
+    }
+
+    fn selected_tree_node(&self) -> Option<&RuleTreeNode>
+    {
+        let selected = self.sidebar_state.selected()?;
+
+        self.visible_tree_nodes().get(selected.checked_sub(1)?).copied()
+    }
+
+    fn next_tree_entry(&mut self)
+    {
+        let selected = self.sidebar_state.selected().map_or(0, |selected| selected + 1);
+        let last = self.visible_tree_nodes().len();
+        self.sidebar_state.select(Some(selected.min(last)));
+    }
+
+    fn previous_tree_entry(&mut self)
+    {
+        let selected = self.sidebar_state.selected().unwrap_or(0);
+        self.sidebar_state.select(Some(selected.saturating_sub(1)));
+    }
+
+    fn apply_tree_filter(&mut self, process_service: &ProcessService)
+    {
+        self.tree_filter = self.selected_tree_node().map(|node| node.path.clone());
+        self.pagination.current_page = 0;
+        self.list_state.select(None);
+        self.update_search(process_service);
+    }
+
+    // Collapses an open folder, or else moves up to the folder holding the selected node.
+    fn collapse_tree_node(&mut self)
+    {
+        let Some(node) = self.selected_tree_node().cloned()
+        else
+        {
+            return;
+        };
+
+        if node.kind != RuleTreeNodeKind::File && self.collapsed_nodes.insert(node.path.clone())
+        {
+            return;
+        }
+
+        let parent_position = self
+            .visible_tree_nodes()
+            .iter()
+            .position(|parent| parent.depth + 1 == node.depth && node.path.starts_with(&parent.path));
+
+        if let Some(parent_position) = parent_position
+        {
+            self.sidebar_state.select(Some(parent_position + 1));
+        }
+    }
+
+    fn expand_tree_node(&mut self)
+    {
+        if let Some(node) = self.selected_tree_node().cloned()
+        {
+            self.collapsed_nodes.remove(&node.path);
+        }
+    }
+
+    fn diagnostics(&self) -> Vec<&Diagnostic>
+    {
+        self.rules_errors.iter().chain(&self.save_errors).collect()
+    }
+
+    fn next_diagnostic(&mut self)
+    {
+        let selected = self.diagnostics_state.selected().map_or(0, |selected| selected + 1);
+        let last = self.diagnostics().len().saturating_sub(1);
+        self.diagnostics_state.select(Some(selected.min(last)));
+    }
+
+    fn previous_diagnostic(&mut self)
+    {
+        let selected = self.diagnostics_state.selected().unwrap_or(0);
+        self.diagnostics_state.select(Some(selected.saturating_sub(1)));
+    }
+
     fn previous_page(&mut self)
     {
-        if self.current_page > 0
+        if self.pagination.previous_page()
         {
-            self.current_page -= 1;
             self.list_state.select(Some(0));
         }
     }
 
     fn update_search(&mut self, process_service: &ProcessService)
     {
-        let selected_rule_name = self
-            .list_state
-            .selected()
-            .and_then(|idx| {
-                let real_idx = self.current_page * self.items_per_page + idx;
-                self.filtered_rules.get(real_idx)
-            })
-            .and_then(|rule| rule.data.name.clone());
+        let selected_rule_name = self.selected_rule().and_then(|rule| rule.data.name.clone());
 
-        let query = self.search_query.to_lowercase();
+        // An unfinished query keeps the last results until it parses again.
+        match Query::parse(&self.search_query)
+        {
+            Ok(query) =>
+            {
+                self.search = query;
+                self.search_error = None;
+            }
+            Err(e) =>
+            {
+                self.search_error = Some(e.to_string());
+                return;
+            }
+        }
 
-        self.filtered_rules = self
-            .all_rules
-            .iter()
-            .filter(|rule| {
-                if self.filter_active_only
-                {
-                    let original_name = rule.data.name.as_deref().unwrap_or("");
-                    if !process_service.is_process_active(original_name)
-                    {
-                        return false;
-                    }
-                }
+        let is_active = |rule_name: &str| process_service.is_process_active(rule_name);
 
-                let match_str = |opt: &Option<String>| opt.as_deref().unwrap_or("").to_lowercase().contains(&query);
+        let search = if self.filter_active_only
+        {
+            Query::And(vec![Query::Term(Term::Active(true)), self.search.clone()])
+        }
+        else
+        {
+            self.search.clone()
+        };
 
-                let match_num = |opt: &Option<i32>| opt.map(|n| n.to_string()).unwrap_or_default().contains(&query);
+        self.filtered_rules = RuleService::rank_rules(&self.all_rules, &search, self.search_mode, is_active);
 
-                match_str(&rule.data.name)
-                    || match_str(&rule.data.rule_type)
-                    || match_str(&rule.data.sched)
-                    || match_str(&rule.data.ioclass)
-                    || match_str(&rule.data.cgroup)
-                    || match_str(&rule.context_comment)
-                    || match_num(&rule.data.nice)
-                    || match_num(&rule.data.latency_nice)
-                    || match_num(&rule.data.rtprio)
-                    || match_num(&rule.data.oom_score_adj)
-            })
-            .cloned()
-            .collect();
+        if let Some(tree_filter) = &self.tree_filter
+        {
+            self.filtered_rules
+                .retain(|rule| rule.source_file.starts_with(tree_filter));
+        }
 
-        if let Some(name) = selected_rule_name
+        if let Some(rule_sort) = self.rule_sort
         {
-            if let Some(new_idx) = self
+            RuleService::sort_rules_by(&mut self.filtered_rules, rule_sort, is_active);
+        }
+
+        if let Some(name) = selected_rule_name
+            && let Some(new_idx) = self
                 .filtered_rules
                 .iter()
                 .position(|r| r.data.name == Some(name.clone()))
+        {
+            self.select_index(new_idx);
+            return;
+        }
+
+        self.select_index(0);
+    }
+
+    // Recomputed whenever the processes, the rules or the matching mode change.
+    fn refresh_process_views(&mut self, process_service: &ProcessService)
+    {
+        self.process_tree = process_service.process_tree();
+        self.rule_matches = process_service
+            .match_rules(&self.all_rules)
+            .into_iter()
+            .map(|(process_id, (rule, match_kind))| (process_id, (rule.clone(), match_kind)))
+            .collect();
+
+        // The tree is in pre-order, so parents are always resolved before their children.
+        self.inherited_rules.clear();
+        for node in &self.process_tree
+        {
+            let inherited_rule = node.parent_process_id.and_then(|parent_id| {
+                self.rule_matches
+                    .get(&parent_id)
+                    .map(|(rule, _)| rule)
+                    .or_else(|| self.inherited_rules.get(&parent_id))
+            });
+
+            if let Some(rule) = inherited_rule
             {
-                self.current_page = new_idx / self.items_per_page;
-                let visual_idx = new_idx % self.items_per_page;
-                self.list_state.select(Some(visual_idx));
-                return;
+                self.inherited_rules.insert(node.process_id, rule.clone());
             }
         }
 
-        self.current_page = 0;
+        self.has_matched_descendant.clear();
+        for node in self.process_tree.iter().rev()
+        {
+            if (self.rule_matches.contains_key(&node.process_id)
+                || self.has_matched_descendant.contains(&node.process_id))
+                && let Some(parent_id) = node.parent_process_id
+            {
+                self.has_matched_descendant.insert(parent_id);
+            }
+        }
+
+        self.statistics = RuleService::compute_statistics(&self.all_rules, &self.rules_errors, |rule_name| {
+            process_service.is_process_active(rule_name)
+        });
+    }
+
+    fn watch_processes(&mut self, worker: &RefreshWorker)
+    {
+        let mut process_ids: HashSet<i32> = self
+            .rule_matches
+            .keys()
+            .chain(self.inherited_rules.keys())
+            .copied()
+            .collect();
+
+        if self.view == View::ProcessTree
+        {
+            process_ids.extend(&self.visible_tree_processes);
+        }
 
-        if self.filtered_rules.is_empty()
+        if process_ids != self.watched_processes
         {
-            self.list_state.select(None);
+            worker.watch_processes(process_ids.clone());
+            self.watched_processes = process_ids;
         }
-        else
+    }
+
+    fn handle_worker_event(&mut self, worker_event: WorkerEvent, process_service: &mut ProcessService)
+    {
+        match worker_event
         {
-            self.list_state.select(Some(0));
+            WorkerEvent::ScanStarted =>
+            {
+                self.scanning_processes = true;
+            }
+
+            WorkerEvent::ProcessesScanned(scan_delta) =>
+            {
+                process_service.apply_scan(scan_delta);
+                self.scanning_processes = false;
+                self.refresh_process_views(process_service);
+
+                // Sorting by the Active column depends on the processes as much as the filters do.
+                let sorted_by_active = self
+                    .rule_sort
+                    .is_some_and(|rule_sort| rule_sort.column == RuleColumn::Active);
+
+                if self.filter_active_only || self.search.depends_on_processes() || sorted_by_active
+                {
+                    self.update_search(process_service);
+                }
+            }
+
+            WorkerEvent::AttributesRead(process_attributes) =>
+            {
+                process_service.apply_attributes(process_attributes);
+            }
+
+            WorkerEvent::RulesLoaded(Ok((rules, errors))) =>
+            {
+                self.all_rules = rules;
+                self.rules_errors = errors;
+                self.loading_rules = false;
+                self.rule_tree = RuleService::rule_tree(&self.rule_roots, &self.all_rules);
+
+                let loaded_rules: HashSet<(PathBuf, String)> =
+                    self.all_rules.iter().map(RuleService::mark_key).collect();
+                self.marked_rules.retain(|key| loaded_rules.contains(key));
+
+                // A folder or file that no longer holds rules stops filtering.
+                if let Some(tree_filter) = &self.tree_filter
+                    && !self.rule_tree.iter().any(|node| node.path == *tree_filter)
+                {
+                    self.tree_filter = None;
+                }
+
+                self.refresh_process_views(process_service);
+                self.update_search(process_service);
+
+                if let Some((file, line)) = self.reselect_rule.take()
+                    && let Some(index) = self
+                        .filtered_rules
+                        .iter()
+                        .position(|rule| rule.source_file == file && rule.line_number == line)
+                {
+                    self.select_index(index);
+                }
+            }
+
+            WorkerEvent::RulesLoaded(Err(e)) =>
+            {
+                self.loading_rules = false;
+                self.notification = Some((format!("Error loading rules: {}", e), Color::Red));
+                self.notification_time = Some(Instant::now());
+            }
+
+            WorkerEvent::CgroupsLoaded(Ok((cgroup_definitions, errors))) =>
+            {
+                self.cgroup_definitions = cgroup_definitions;
+                self.rules_errors.extend(errors);
+                self.refresh_process_views(process_service);
+            }
+
+            WorkerEvent::CgroupsLoaded(Err(e)) =>
+            {
+                self.notification = Some((format!("Error loading cgroups: {}", e), Color::Red));
+                self.notification_time = Some(Instant::now());
+            }
+
+            WorkerEvent::TypesLoaded(Ok((type_definitions, errors))) =>
+            {
+                self.type_definitions = type_definitions;
+                self.rules_errors.extend(errors);
+                self.refresh_process_views(process_service);
+            }
+
+            WorkerEvent::TypesLoaded(Err(e)) =>
+            {
+                self.notification = Some((format!("Error loading types: {}", e), Color::Red));
+                self.notification_time = Some(Instant::now());
+            }
         }
     }
 
     fn get_field_value(&self, index: usize) -> String
     {
-        match index
-        {
-            0 => self.editing_rule.name.clone().unwrap_or_default(),
-            1 => self.editing_rule.rule_type.clone().unwrap_or_default(),
-            2 => self.editing_rule.nice.map(|v| v.to_string()).unwrap_or_default(),
-            3 => self
-                .editing_rule
-                .latency_nice
-                .map(|v| v.to_string())
-                .unwrap_or_default(),
-            4 => self.editing_rule.sched.clone().unwrap_or_default(),
-            5 => self.editing_rule.rtprio.map(|v| v.to_string()).unwrap_or_default(),
-            6 => self.editing_rule.ioclass.clone().unwrap_or_default(),
-            7 => self
-                .editing_rule
-                .oom_score_adj
-                .map(|v| v.to_string())
-                .unwrap_or_default(),
-            8 => self.editing_rule.cgroup.clone().unwrap_or_default(),
-            _ => String::new(),
-        }
+        self.editing_values.get(index).cloned().unwrap_or_default()
     }
 
+    // The form keeps the text as typed, the rule previewed next to it only takes the values that parse.
     fn set_field_value(&mut self, index: usize, value: String)
     {
-        let value = if value.trim().is_empty() { None } else { Some(value) };
+        if let Some(field) = AnanicyRule::FIELDS.get(index)
+            && self.editing_rule.set_field(field, &value).is_err()
+        {
+            let _ = self.editing_rule.set_field(field, "");
+        }
 
-        match index
+        if let Some(editing_value) = self.editing_values.get_mut(index)
         {
-            0 => self.editing_rule.name = value,
-            1 => self.editing_rule.rule_type = value,
-            2 => self.editing_rule.nice = value.as_deref().and_then(|v| v.parse().ok()),
-            3 => self.editing_rule.latency_nice = value.as_deref().and_then(|v| v.parse().ok()),
-            4 => self.editing_rule.sched = value,
-            5 => self.editing_rule.rtprio = value.as_deref().and_then(|v| v.parse().ok()),
-            6 => self.editing_rule.ioclass = value,
-            7 => self.editing_rule.oom_score_adj = value.as_deref().and_then(|v| v.parse().ok()),
-            8 => self.editing_rule.cgroup = value,
-            _ =>
-            {}
+            *editing_value = value.trim().to_string();
         }
     }
 
-    fn start_editing(&mut self, rule: Option<AnanicyRule>)
+    // Existing rules are saved back into the file they were loaded from unless another target is picked.
+    fn start_editing(&mut self, rule: Option<EnrichedRule>)
     {
-        self.editing_rule = rule.unwrap_or_default();
+        let target_file = rule
+            .as_ref()
+            .map(|rule| rule.source_file.display().to_string())
+            .unwrap_or_default();
+        self.editing_rule = rule.as_ref().map(|rule| rule.data.clone()).unwrap_or_default();
+        self.editing_original = rule;
+        self.editing_values = AnanicyRule::FIELDS
+            .iter()
+            .map(|field| self.editing_rule.get_field(field).unwrap_or_default())
+            .chain([target_file])
+            .collect();
         self.editing_field_index = 0;
         self.editing_buffer = self.get_field_value(0);
         self.input_mode = InputMode::RuleForm;
         self.process_list.clear();
         self.process_list_state.select(None);
+        self.update_field_options("");
+    }
+
+    fn is_editing_rule(&self) -> bool
+    {
+        matches!(self.input_mode, InputMode::RuleForm | InputMode::ConfirmSave)
     }
 
     fn save_field_buffer(&mut self)
@@ -274,23 +829,161 @@ impl App
     fn move_edit_field(&mut self, delta: i32)
     {
         self.save_field_buffer();
-        let new_index = (self.editing_field_index as i32 + delta).rem_euclid(9) as usize;
+        let new_index = (self.editing_field_index as i32 + delta).rem_euclid(FORM_FIELD_COUNT as i32) as usize;
         self.editing_field_index = new_index;
         self.editing_buffer = self.get_field_value(new_index);
         // Do not clear process list, keep it for reference
+        self.update_field_options("");
     }
 
-    fn update_process_search(&mut self, process_service: &ProcessService)
+    // The values a picker field offers, empty for free text fields.
+    fn field_choices(&self, index: usize) -> Vec<String>
     {
-        if self.input_mode == InputMode::RuleForm
+        let mut choices: Vec<String> = match index
         {
-            // Always update suggestions based on name field (index 0)
-            let query = if self.editing_field_index == 0
-            {
-                self.editing_buffer.clone()
-            }
-            else
-            {
+            TYPE_FIELD => self
+                .type_definitions
+                .iter()
+                .filter_map(|definition| definition.name.clone())
+                .collect(),
+            SCHED_FIELD => return AnanicyRule::SCHED_POLICIES.map(String::from).to_vec(),
+            IOCLASS_FIELD => return AnanicyRule::IO_CLASSES.map(String::from).to_vec(),
+            CGROUP_FIELD => self
+                .cgroup_definitions
+                .iter()
+                .filter_map(|definition| definition.name.clone())
+                .collect(),
+            TARGET_FILE_FIELD => RuleService::rule_files(&self.all_rules)
+                .iter()
+                .map(|rule_file| rule_file.display().to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        choices.sort();
+        choices.dedup();
+
+        choices
+    }
+
+    // Entering a field lists every choice with the current value selected, typing narrows the list down.
+    fn update_field_options(&mut self, query: &str)
+    {
+        let query = query.to_lowercase();
+
+        self.field_options = self
+            .field_choices(self.editing_field_index)
+            .into_iter()
+            .filter(|choice| choice.to_lowercase().contains(&query))
+            .collect();
+
+        let selected = self
+            .field_options
+            .iter()
+            .position(|choice| *choice == self.editing_buffer)
+            .or(if self.field_options.is_empty() { None } else { Some(0) });
+        self.field_options_state.select(selected);
+    }
+
+    // Steps through a picker's choices, with an empty value before the first one to leave the field unset.
+    fn cycle_field_choice(&mut self, delta: i32)
+    {
+        let choices = self.field_choices(self.editing_field_index);
+
+        if choices.is_empty()
+        {
+            return;
+        }
+
+        let current = choices
+            .iter()
+            .position(|choice| *choice == self.editing_buffer)
+            .map_or(0, |position| position + 1);
+        let next = (current as i32 + delta).rem_euclid(choices.len() as i32 + 1) as usize;
+
+        self.editing_buffer = if next == 0
+        {
+            String::new()
+        }
+        else
+        {
+            choices[next - 1].clone()
+        };
+        self.update_field_options("");
+    }
+
+    // One entry per form field, None where the field is valid. Types and cgroups are only checked against
+    // definitions once some were loaded.
+    fn field_errors(&self) -> Vec<Option<String>>
+    {
+        let value = |index: usize| {
+            if index == self.editing_field_index
+            {
+                self.editing_buffer.clone()
+            }
+            else
+            {
+                self.get_field_value(index)
+            }
+        };
+
+        let mut errors = vec![None; FORM_FIELD_COUNT];
+        let mut rule = AnanicyRule::default();
+
+        for (index, field) in AnanicyRule::FIELDS.iter().enumerate()
+        {
+            if let Err(e) = rule.set_field(field, &value(index))
+            {
+                errors[index] = Some(e.to_string());
+            }
+        }
+
+        for (field, problem) in rule.problems()
+        {
+            if let Some(index) = AnanicyRule::FIELDS.iter().position(|name| *name == field)
+            {
+                errors[index].get_or_insert(problem);
+            }
+        }
+
+        if let Some(rule_type) = rule.rule_type.as_deref()
+            && !self.type_definitions.is_empty()
+            && !self
+                .type_definitions
+                .iter()
+                .any(|definition| definition.name.as_deref() == Some(rule_type))
+        {
+            errors[TYPE_FIELD].get_or_insert(format!("type '{}' is not defined in any .types file", rule_type));
+        }
+
+        if let Some(cgroup) = rule.cgroup.as_deref()
+            && !self.cgroup_definitions.is_empty()
+            && RuleService::find_cgroup_definition(&self.cgroup_definitions, cgroup).is_none()
+        {
+            errors[CGROUP_FIELD].get_or_insert(format!("cgroup '{}' is not defined in any .cgroups file", cgroup));
+        }
+
+        let target_file = value(TARGET_FILE_FIELD);
+
+        if !target_file.is_empty() && !target_file.ends_with(".rules")
+        {
+            errors[TARGET_FILE_FIELD] = Some("target file must have the .rules extension".to_string());
+        }
+
+        errors
+    }
+
+    fn update_process_search(&mut self, process_service: &ProcessService)
+    {
+        if self.input_mode == InputMode::RuleForm
+        {
+            // Always update suggestions based on name field (index 0)
+            let query = if self.editing_field_index == 0
+            {
+                self.editing_buffer.clone()
+            }
+            else
+            {
                 self.get_field_value(0)
             };
 
@@ -311,10 +1004,8 @@ impl App
     }
 }
 
-pub fn run_app(rule_service: &RuleService, process_service: &mut ProcessService) -> Result<()>
+pub fn run_app(rule_service: &RuleService, process_service: &mut ProcessService, config: &AppConfig) -> Result<()>
 {
-    let (rules, errors) = rule_service.search_rules("")?;
-
     enable_raw_mode()?;
 
     let mut stdout = io::stdout();
@@ -323,236 +1014,603 @@ pub fn run_app(rule_service: &RuleService, process_service: &mut ProcessService)
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(rules, errors);
+    let mut app = App::new(Vec::new(), Vec::new());
+    app.rule_roots = rule_service.rule_roots().to_vec();
+    app.max_items_per_page = config.items_per_page.max(1);
+    app.theme = config.theme;
+    app.editor_line_arg = config.editor_line_arg.clone();
+
+    let tick_rate = Duration::from_millis(config.tick_rate_ms);
+    let scanner = process_service.take_scanner();
+
+    // From here on the worker reads the attributes, processes it was not asked about show none.
+    if scanner.is_some()
+    {
+        process_service.apply_attributes(ProcessAttributes::default());
+    }
+    let cgroup2_mount = process_service
+        .capabilities()
+        .and_then(|capabilities| capabilities.cgroup2_mount.clone());
+
+    let result = thread::scope(|scope| {
+        let worker = RefreshWorker::spawn(scope, rule_service, scanner, cgroup2_mount, tick_rate);
 
-    let tick_rate = Duration::from_secs(1);
-    let mut last_tick = Instant::now();
+        app.loading_rules = true;
+        worker.reload_rules();
+
+        let result = run_event_loop(&mut terminal, &mut app, &worker, rule_service, process_service);
+        worker.shutdown();
+
+        result
+    });
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_event_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    worker: &RefreshWorker,
+    rule_service: &RuleService,
+    process_service: &mut ProcessService,
+) -> Result<()>
+{
+    let poll_interval = Duration::from_millis(100);
 
     loop
     {
-        terminal.draw(|frame| ui(frame, &mut app, process_service))?;
+        while let Some(worker_event) = worker.try_recv()
+        {
+            app.handle_worker_event(worker_event, process_service);
+        }
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
+        terminal.draw(|frame| ui(frame, app, process_service))?;
+        app.watch_processes(worker);
 
-        if event::poll(timeout)?
+        if event::poll(poll_interval)?
+            && let Event::Key(key) = event::read()?
         {
-            if let Event::Key(key) = event::read()?
+            match app.input_mode
             {
-                match app.input_mode
+                InputMode::Normal => match key.code
                 {
-                    InputMode::Normal => match key.code
+                    KeyCode::Char('t') | KeyCode::Esc if app.is_sidebar_active() =>
+                    {
+                        app.sidebar_focused = false;
+                    }
+                    KeyCode::Char('t') if app.view == View::Rules =>
+                    {
+                        app.sidebar_focused = true;
+                        if app.sidebar_state.selected().is_none()
+                        {
+                            app.sidebar_state.select(Some(0));
+                        }
+                    }
+                    KeyCode::Down if app.is_sidebar_active() => app.next_tree_entry(),
+                    KeyCode::Up if app.is_sidebar_active() => app.previous_tree_entry(),
+                    KeyCode::Left if app.is_sidebar_active() => app.collapse_tree_node(),
+                    KeyCode::Right if app.is_sidebar_active() => app.expand_tree_node(),
+                    KeyCode::Enter if app.is_sidebar_active() => app.apply_tree_filter(process_service),
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Tab =>
+                    {
+                        app.view = match app.view
+                        {
+                            View::Rules => View::ProcessTree,
+                            View::ProcessTree => View::Dashboard,
+                            View::Dashboard => View::Diagnostics,
+                            View::Diagnostics => View::Rules,
+                        };
+                    }
+                    KeyCode::Down if app.view == View::Diagnostics => app.next_diagnostic(),
+                    KeyCode::Up if app.view == View::Diagnostics => app.previous_diagnostic(),
+                    KeyCode::Enter if app.view == View::Diagnostics =>
+                    {
+                        let selected = app
+                            .diagnostics_state
+                            .selected()
+                            .and_then(|selected| app.diagnostics().get(selected).cloned().cloned());
+
+                        if let Some(Diagnostic {
+                            file: Some(file), line, ..
+                        }) = selected
+                        {
+                            edit_and_reload(terminal, app, worker, &file, line);
+                        }
+                    }
+                    KeyCode::Char('a') if app.view == View::ProcessTree =>
+                    {
+                        app.tree_matched_only = !app.tree_matched_only;
+                        app.tree_state.select(Some(0));
+                    }
+                    KeyCode::Down if app.view == View::ProcessTree => app.next_tree_node(),
+                    KeyCode::Up if app.view == View::ProcessTree => app.previous_tree_node(),
+                    KeyCode::Char('s') | KeyCode::Char('/') =>
+                    {
+                        app.input_mode = InputMode::Editing;
+                    }
+                    KeyCode::Char('n') =>
                     {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Char('s') | KeyCode::Char('/') =>
+                        if app.is_root
+                        {
+                            app.start_editing(None);
+                            app.update_process_search(process_service);
+                        }
+                        else
                         {
-                            app.input_mode = InputMode::Editing;
+                            app.notification = Some(("Root required to create new rules.".to_string(), Color::Red));
+                            app.notification_time = Some(Instant::now());
                         }
-                        KeyCode::Char('n') =>
+                    }
+                    KeyCode::Char('e') =>
+                    {
+                        if app.is_root
                         {
-                            if app.is_root
+                            if let Some(rule) = app.selected_rule().cloned()
                             {
-                                app.start_editing(None);
+                                app.start_editing(Some(rule));
                                 app.update_process_search(process_service);
                             }
-                            else
-                            {
-                                app.notification = Some(("Root required to create new rules.".to_string(), Color::Red));
-                                app.notification_time = Some(Instant::now());
-                            }
                         }
-                        KeyCode::Char('e') =>
+                        else
                         {
-                            if app.is_root
-                            {
-                                if let Some(selected) = app.list_state.selected()
-                                {
-                                    let start = app.current_page * app.items_per_page;
-                                    let real_idx = start + selected;
-                                    if let Some(rule) = app.filtered_rules.get(real_idx)
-                                    {
-                                        app.start_editing(Some(rule.data.clone()));
-                                        app.update_process_search(process_service);
-                                    }
-                                }
-                            }
-                            else
-                            {
-                                app.notification = Some(("Root required to edit rules.".to_string(), Color::Red));
-                                app.notification_time = Some(Instant::now());
-                            }
+                            app.notification = Some(("Root required to edit rules.".to_string(), Color::Red));
+                            app.notification_time = Some(Instant::now());
                         }
-                        KeyCode::Char('a') =>
+                    }
+                    KeyCode::Char('o') if app.view == View::Rules =>
+                    {
+                        if let Some(rule) = app.selected_rule().cloned()
                         {
-                            app.filter_active_only = !app.filter_active_only;
-                            app.update_search(process_service);
+                            edit_and_reload(terminal, app, worker, &rule.source_file, Some(rule.line_number));
                         }
-                        KeyCode::Down => app.next(),
-                        KeyCode::Up => app.previous(),
-                        KeyCode::Right => app.next_page(),
-                        KeyCode::Left => app.previous_page(),
-                        _ =>
-                        {}
-                    },
-                    InputMode::Editing => match key.code
+                    }
+                    KeyCode::Char('>') if app.view == View::Rules => app.cycle_sort_column(1, process_service),
+                    KeyCode::Char('<') if app.view == View::Rules => app.cycle_sort_column(-1, process_service),
+                    KeyCode::Char('r') if app.view == View::Rules => app.reverse_sort(process_service),
+                    KeyCode::Char('c') if app.view == View::Rules =>
+                    {
+                        app.column_picker_state.select(Some(0));
+                        app.input_mode = InputMode::ColumnPicker;
+                    }
+                    KeyCode::Char('a') =>
+                    {
+                        app.filter_active_only = !app.filter_active_only;
+                        app.update_search(process_service);
+                    }
+                    KeyCode::Char('f') =>
+                    {
+                        app.search_mode = match app.search_mode
+                        {
+                            SearchMode::Exact => SearchMode::Fuzzy,
+                            SearchMode::Fuzzy => SearchMode::Exact,
+                        };
+                        app.update_search(process_service);
+                    }
+                    KeyCode::Char('m') =>
+                    {
+                        let matching_mode = match process_service.matching_mode()
+                        {
+                            MatchingMode::Strict => MatchingMode::Discovery,
+                            MatchingMode::Discovery => MatchingMode::Strict,
+                        };
+
+                        process_service.set_matching_mode(matching_mode);
+                        app.refresh_process_views(process_service);
+                        app.update_search(process_service);
+                    }
+                    KeyCode::Char(' ') if app.view == View::Rules => app.toggle_selected_mark(),
+                    KeyCode::Char('*') if app.view == View::Rules => app.toggle_all_marks(),
+                    KeyCode::Char('b') if app.view == View::Rules =>
                     {
-                        KeyCode::Esc | KeyCode::Enter =>
+                        if !app.is_root
                         {
-                            app.input_mode = InputMode::Normal;
+                            app.notification = Some(("Root required to edit rules.".to_string(), Color::Red));
+                            app.notification_time = Some(Instant::now());
                         }
-                        KeyCode::Backspace =>
+                        else if app.marked_rules.is_empty()
                         {
-                            app.search_query.pop();
-                            app.update_search(process_service);
+                            app.notification = Some((t!("bulk_nothing_marked").to_string(), Color::Red));
+                            app.notification_time = Some(Instant::now());
                         }
-                        KeyCode::Char(c) =>
+                        else
                         {
-                            app.search_query.push(c);
-                            app.update_search(process_service);
+                            app.bulk_menu_state.select(Some(0));
+                            app.input_mode = InputMode::BulkMenu;
                         }
-                        _ =>
-                        {}
-                    },
-                    InputMode::RuleForm => match key.code
+                    }
+                    KeyCode::Char('g') if app.view == View::Rules =>
+                    {
+                        app.jump_prefix.clear();
+                        app.input_mode = InputMode::Jump;
+                    }
+                    KeyCode::Down => app.scroll_by(1),
+                    KeyCode::Up => app.scroll_by(-1),
+                    KeyCode::PageDown => app.scroll_by(app.pagination.items_per_page as isize),
+                    KeyCode::PageUp => app.scroll_by(-(app.pagination.items_per_page as isize)),
+                    KeyCode::Home => app.select_index(0),
+                    KeyCode::End => app.select_index(usize::MAX),
+                    KeyCode::Right => app.next_page(),
+                    KeyCode::Left => app.previous_page(),
+                    _ =>
+                    {}
+                },
+                InputMode::Editing => match key.code
+                {
+                    KeyCode::Esc | KeyCode::Enter =>
+                    {
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Backspace =>
+                    {
+                        app.search_query.pop();
+                        app.update_search(process_service);
+                    }
+                    KeyCode::Char(c) =>
+                    {
+                        app.search_query.push(c);
+                        app.update_search(process_service);
+                    }
+                    _ =>
+                    {}
+                },
+                InputMode::RuleForm => match key.code
+                {
+                    KeyCode::Esc =>
+                    {
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Tab =>
+                    {
+                        app.move_edit_field(1);
+                    }
+                    KeyCode::BackTab =>
                     {
-                        KeyCode::Esc =>
+                        app.move_edit_field(-1);
+                    }
+                    KeyCode::Left =>
+                    {
+                        app.cycle_field_choice(-1);
+                    }
+                    KeyCode::Right =>
+                    {
+                        app.cycle_field_choice(1);
+                    }
+                    KeyCode::Down if !app.field_options.is_empty() =>
+                    {
+                        let current = app.field_options_state.selected().unwrap_or(0);
+                        let next = (current + 1).min(app.field_options.len() - 1);
+                        app.field_options_state.select(Some(next));
+                    }
+                    KeyCode::Up if !app.field_options.is_empty() =>
+                    {
+                        let current = app.field_options_state.selected().unwrap_or(0);
+                        app.field_options_state.select(Some(current.saturating_sub(1)));
+                    }
+                    KeyCode::Enter if !app.field_options.is_empty() =>
+                    {
+                        if let Some(choice) = app
+                            .field_options_state
+                            .selected()
+                            .and_then(|idx| app.field_options.get(idx))
                         {
-                            app.input_mode = InputMode::Normal;
+                            app.editing_buffer = choice.clone();
                         }
-                        KeyCode::Tab =>
+                        app.move_edit_field(1);
+                    }
+                    KeyCode::Down =>
+                    {
+                        if app.editing_field_index == 0 && !app.process_list.is_empty()
                         {
-                            app.move_edit_field(1);
+                            let current = app.process_list_state.selected().unwrap_or(0);
+                            let next = (current + 1).min(app.process_list.len() - 1);
+                            app.process_list_state.select(Some(next));
                         }
-                        KeyCode::BackTab =>
+                        else
                         {
-                            app.move_edit_field(-1);
+                            app.move_edit_field(1);
                         }
-                        KeyCode::Down =>
+                    }
+                    KeyCode::Up =>
+                    {
+                        if app.editing_field_index == 0 && !app.process_list.is_empty()
                         {
-                            if app.editing_field_index == 0 && !app.process_list.is_empty()
+                            let current = app.process_list_state.selected().unwrap_or(0);
+                            if current > 0
                             {
-                                let current = app.process_list_state.selected().unwrap_or(0);
-                                let next = (current + 1).min(app.process_list.len() - 1);
-                                app.process_list_state.select(Some(next));
+                                app.process_list_state.select(Some(current - 1));
                             }
                             else
                             {
-                                app.move_edit_field(1);
+                                app.move_edit_field(-1);
                             }
                         }
-                        KeyCode::Up =>
+                        else
                         {
-                            if app.editing_field_index == 0 && !app.process_list.is_empty()
-                            {
-                                let current = app.process_list_state.selected().unwrap_or(0);
-                                if current > 0
-                                {
-                                    app.process_list_state.select(Some(current - 1));
-                                }
-                                else
-                                {
-                                    app.move_edit_field(-1);
-                                }
-                            }
-                            else
-                            {
-                                app.move_edit_field(-1);
-                            }
+                            app.move_edit_field(-1);
                         }
-                        KeyCode::Enter =>
+                    }
+                    KeyCode::Enter =>
+                    {
+                        if app.editing_field_index == 0 && !app.process_list.is_empty()
                         {
-                            if app.editing_field_index == 0 && !app.process_list.is_empty()
-                            {
-                                if let Some(idx) = app.process_list_state.selected()
-                                {
-                                    if let Some(selected_process) = app.process_list.get(idx)
-                                    {
-                                        let first_part =
-                                            selected_process.split_whitespace().next().unwrap_or(selected_process);
-                                        let name = std::path::Path::new(first_part)
-                                            .file_name()
-                                            .and_then(|n| n.to_str())
-                                            .unwrap_or(first_part);
-
-                                        app.editing_buffer = name.to_string();
-                                        // Keep list visible but filtered
-                                        // app.process_list.clear();
-                                    }
-                                }
-                                app.move_edit_field(1);
-                            }
-                            else
+                            if let Some(idx) = app.process_list_state.selected()
+                                && let Some(selected_process) = app.process_list.get(idx)
                             {
-                                app.move_edit_field(1);
+                                let first_part = selected_process.split_whitespace().next().unwrap_or(selected_process);
+                                let name = std::path::Path::new(first_part)
+                                    .file_name()
+                                    .and_then(|n| n.to_str())
+                                    .unwrap_or(first_part);
+
+                                app.editing_buffer = name.to_string();
+                                // Keep list visible but filtered
+                                // app.process_list.clear();
                             }
+                            app.move_edit_field(1);
                         }
-                        KeyCode::Backspace =>
+                        else
                         {
-                            app.editing_buffer.pop();
-                            if app.editing_field_index == 0
-                            {
-                                app.update_process_search(process_service);
-                            }
+                            app.move_edit_field(1);
+                        }
+                    }
+                    KeyCode::Backspace =>
+                    {
+                        app.editing_buffer.pop();
+                        if app.editing_field_index == 0
+                        {
+                            app.update_process_search(process_service);
+                        }
+                        else
+                        {
+                            let query = app.editing_buffer.clone();
+                            app.update_field_options(&query);
+                        }
+                    }
+                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                    {
+                        app.save_field_buffer();
+                        let target_file = app.get_field_value(TARGET_FILE_FIELD);
+                        let target_file = (!target_file.is_empty()).then(|| Path::new(&target_file));
+
+                        if let Some(problem) = app.field_errors().into_iter().flatten().next()
+                        {
+                            app.notification =
+                                Some((t!("form_fix_before_saving", problem = problem).to_string(), Color::Red));
                         }
-                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        else
                         {
-                            app.save_field_buffer();
-                            match rule_service.save_rule(&app.editing_rule)
+                            let save_plan = match &app.editing_original
+                            {
+                                Some(original) => rule_service.plan_edit_rule(original, &app.editing_rule, target_file),
+                                None => rule_service.plan_save_rule(&app.editing_rule, target_file),
+                            };
+
+                            match save_plan
                             {
-                                Ok(_) =>
+                                Ok(save_plan) =>
                                 {
-                                    app.notification = Some(("Rule saved successfully!".to_string(), Color::Green));
-                                    app.input_mode = InputMode::Normal;
-                                    // Reload rules
-                                    if let Ok((rules, errors)) = rule_service.search_rules("")
-                                    {
-                                        app.all_rules = rules;
-                                        app.rules_errors.extend(errors);
-                                        app.update_search(process_service);
-                                    }
+                                    app.pending_save = Some(save_plan);
+                                    app.preview_scroll = 0;
+                                    app.input_mode = InputMode::ConfirmSave;
                                 }
                                 Err(e) =>
                                 {
                                     app.notification = Some((format!("Error saving: {}", e), Color::Red));
+                                    app.save_errors.push(Diagnostic::new(format!("Error saving: {:#}", e)));
                                 }
                             }
-                            app.notification_time = Some(Instant::now());
                         }
-                        KeyCode::Char(c) =>
+                        app.notification_time = Some(Instant::now());
+                    }
+                    KeyCode::Char(c) =>
+                    {
+                        app.editing_buffer.push(c);
+                        if app.editing_field_index == 0
                         {
-                            app.editing_buffer.push(c);
-                            if app.editing_field_index == 0
-                            {
-                                app.update_process_search(process_service);
-                            }
+                            app.update_process_search(process_service);
+                        }
+                        else
+                        {
+                            let query = app.editing_buffer.clone();
+                            app.update_field_options(&query);
+                        }
+                    }
+                    _ =>
+                    {}
+                },
+                InputMode::BulkMenu => match key.code
+                {
+                    KeyCode::Esc =>
+                    {
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Down =>
+                    {
+                        let current = app.bulk_menu_state.selected().unwrap_or(0);
+                        app.bulk_menu_state
+                            .select(Some((current + 1).min(BULK_ACTIONS.len() - 1)));
+                    }
+                    KeyCode::Up =>
+                    {
+                        let current = app.bulk_menu_state.selected().unwrap_or(0);
+                        app.bulk_menu_state.select(Some(current.saturating_sub(1)));
+                    }
+                    KeyCode::Enter => match app.bulk_menu_state.selected()
+                    {
+                        Some(BULK_SET_FIELD | BULK_MOVE) =>
+                        {
+                            app.bulk_input.clear();
+                            app.input_mode = InputMode::BulkInput;
                         }
-                        _ =>
-                        {}
+                        Some(BULK_DISABLE) => app.plan_bulk_action(rule_service, BulkAction::Disable),
+                        _ => app.plan_bulk_action(rule_service, BulkAction::Delete),
                     },
-                }
-            }
-        }
-
-        if last_tick.elapsed() >= tick_rate
-        {
-            process_service.update_processes();
-
-            if app.filter_active_only
-            {
-                app.update_search(process_service);
-            }
-
-            last_tick = Instant::now();
-        }
-    }
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
-    Ok(())
-}
+                    _ =>
+                    {}
+                },
+                InputMode::BulkInput => match key.code
+                {
+                    KeyCode::Esc =>
+                    {
+                        app.input_mode = InputMode::BulkMenu;
+                    }
+                    KeyCode::Backspace =>
+                    {
+                        app.bulk_input.pop();
+                    }
+                    KeyCode::Char(c) =>
+                    {
+                        app.bulk_input.push(c);
+                    }
+                    KeyCode::Enter if app.bulk_menu_state.selected() == Some(BULK_MOVE) =>
+                    {
+                        let target_file = PathBuf::from(app.bulk_input.trim());
+                        app.plan_bulk_action(rule_service, BulkAction::MoveTo(target_file));
+                    }
+                    KeyCode::Enter => match app.bulk_input.split_once('=')
+                    {
+                        Some((field, value)) =>
+                        {
+                            let action = BulkAction::SetField {
+                                field: field.trim().to_string(),
+                                value: value.trim().to_string(),
+                            };
+                            app.plan_bulk_action(rule_service, action);
+                        }
+                        None =>
+                        {
+                            app.notification = Some((t!("bulk_expected_field_value").to_string(), Color::Red));
+                            app.notification_time = Some(Instant::now());
+                        }
+                    },
+                    _ =>
+                    {}
+                },
+                InputMode::ConfirmSave | InputMode::ConfirmBulk => match key.code
+                {
+                    KeyCode::Char('y') | KeyCode::Enter =>
+                    {
+                        let is_bulk = app.input_mode == InputMode::ConfirmBulk;
+
+                        if let Some(save_plan) = app.pending_save.take()
+                        {
+                            match rule_service.apply_save_plan(&save_plan)
+                            {
+                                Ok(()) =>
+                                {
+                                    let message = if is_bulk
+                                    {
+                                        app.marked_rules.clear();
+                                        t!("bulk_written", count = save_plan.changes.len()).to_string()
+                                    }
+                                    else
+                                    {
+                                        format!("Rule saved successfully to {}!", save_plan.changes[0].file.display())
+                                    };
+
+                                    app.notification = Some((message, Color::Green));
+                                    app.input_mode = InputMode::Normal;
+                                    app.loading_rules = true;
+                                    worker.reload_rules();
+                                }
+                                Err(e) =>
+                                {
+                                    app.notification = Some((format!("Error saving: {}", e), Color::Red));
+
+                                    let message = format!("Error saving: {:#}", e);
+                                    app.save_errors.push(match save_plan.changes.as_slice()
+                                    {
+                                        [change] => Diagnostic::in_file(&change.file, None, message),
+                                        _ => Diagnostic::new(message),
+                                    });
+
+                                    app.input_mode = if is_bulk
+                                    {
+                                        InputMode::Normal
+                                    }
+                                    else
+                                    {
+                                        InputMode::RuleForm
+                                    };
+                                }
+                            }
+                            app.notification_time = Some(Instant::now());
+                        }
+                    }
+                    KeyCode::Char('n') | KeyCode::Esc =>
+                    {
+                        app.pending_save = None;
+                        app.input_mode = if app.input_mode == InputMode::ConfirmBulk
+                        {
+                            InputMode::BulkMenu
+                        }
+                        else
+                        {
+                            InputMode::RuleForm
+                        };
+                    }
+                    KeyCode::Down | KeyCode::Char('j') =>
+                    {
+                        app.preview_scroll = app.preview_scroll.saturating_add(1);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') =>
+                    {
+                        app.preview_scroll = app.preview_scroll.saturating_sub(1);
+                    }
+                    _ =>
+                    {}
+                },
+                InputMode::Jump => match key.code
+                {
+                    KeyCode::Esc | KeyCode::Enter =>
+                    {
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Backspace =>
+                    {
+                        app.jump_prefix.pop();
+                        app.jump_to_prefix();
+                    }
+                    KeyCode::Char(c) =>
+                    {
+                        app.jump_prefix.push(c);
+                        app.jump_to_prefix();
+                    }
+                    _ =>
+                    {}
+                },
+                InputMode::ColumnPicker => match key.code
+                {
+                    KeyCode::Esc | KeyCode::Char('c') =>
+                    {
+                        app.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Char(' ') | KeyCode::Enter => app.toggle_selected_column(process_service),
+                    KeyCode::Down =>
+                    {
+                        let current = app.column_picker_state.selected().unwrap_or(0);
+                        app.column_picker_state
+                            .select(Some((current + 1).min(RuleColumn::ALL.len() - 1)));
+                    }
+                    KeyCode::Up =>
+                    {
+                        let current = app.column_picker_state.selected().unwrap_or(0);
+                        app.column_picker_state.select(Some(current.saturating_sub(1)));
+                    }
+                    _ =>
+                    {}
+                },
+            }
+        }
+    }
+
+    Ok(())
+}
 
 fn ui(frame: &mut Frame, app: &mut App, process_service: &ProcessService)
 {
@@ -561,43 +1619,222 @@ fn ui(frame: &mut Frame, app: &mut App, process_service: &ProcessService)
         .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(1)])
         .split(frame.area());
 
-    render_search(frame, app, layout_chunks[0]);
+    render_search(frame, app, process_service, layout_chunks[0]);
     render_content(frame, app, process_service, layout_chunks[1]);
 
-    if let Some((msg, color)) = &app.notification
+    if let Some(save_plan) = &app.pending_save
     {
-        if let Some(time) = app.notification_time
+        let title = if app.input_mode == InputMode::ConfirmBulk
         {
-            if time.elapsed() < Duration::from_secs(3)
-            {
-                let notif_area = Rect {
-                    x: layout_chunks[0].x + layout_chunks[0].width / 2
-                        - (msg.len() as u16 / 2).min(layout_chunks[0].width / 2),
-                    y: layout_chunks[0].y + 1,
-                    width: (msg.len() as u16 + 4).min(layout_chunks[0].width),
-                    height: 1,
-                };
-                let notif = Paragraph::new(msg.as_str()).style(
-                    Style::default()
-                        .bg(*color)
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
-                );
-                frame.render_widget(notif, notif_area);
-            }
+            format!(" {} ", t!("bulk_confirm_title"))
         }
+        else
+        {
+            format!(" {} ", t!("save_confirm_title"))
+        };
+
+        render_save_preview(frame, save_plan, &title, app.preview_scroll, layout_chunks[1]);
+    }
+
+    if matches!(app.input_mode, InputMode::BulkMenu | InputMode::BulkInput)
+    {
+        render_bulk_menu(frame, app, layout_chunks[1]);
+    }
+
+    if app.input_mode == InputMode::ColumnPicker
+    {
+        render_column_picker(frame, app, layout_chunks[1]);
+    }
+
+    if let Some((msg, color)) = &app.notification
+        && let Some(time) = app.notification_time
+        && time.elapsed() < Duration::from_secs(3)
+    {
+        let notif_area = Rect {
+            x: layout_chunks[0].x + layout_chunks[0].width / 2 - (msg.len() as u16 / 2).min(layout_chunks[0].width / 2),
+            y: layout_chunks[0].y + 1,
+            width: (msg.len() as u16 + 4).min(layout_chunks[0].width),
+            height: 1,
+        };
+        let notif = Paragraph::new(msg.as_str()).style(
+            Style::default()
+                .bg(*color)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(notif, notif_area);
     }
 
     render_help(frame, app, layout_chunks[2]);
+
+    apply_theme(frame.buffer_mut(), app.theme);
 }
 
-fn render_search(frame: &mut Frame, app: &App, area: Rect)
+// The edited file may fix or add problems and change which rules shadow each other, so everything is reloaded.
+fn edit_and_reload(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    worker: &RefreshWorker,
+    file: &Path,
+    line: Option<usize>,
+)
+{
+    match open_in_editor(terminal, app.editor_line_arg.clone(), file, line)
+    {
+        Ok(()) =>
+        {
+            app.notification = Some((t!("editor_reloading", file = file.display()).to_string(), Color::Green));
+            app.loading_rules = true;
+            app.reselect_rule = line.map(|line| (file.to_path_buf(), line));
+            worker.reload_rules();
+        }
+        Err(e) =>
+        {
+            app.notification = Some((t!("editor_open_failed", error = e).to_string(), Color::Red));
+        }
+    }
+    app.notification_time = Some(Instant::now());
+}
+
+// Hands the terminal to the editor and takes it back once the editor exits.
+fn open_in_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    line_argument: Option<String>,
+    file: &Path,
+    line: Option<usize>,
+) -> Result<()>
+{
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+
+    let result = EditorLauncher::new_with_line_argument(line_argument).open(file, line);
+
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+
+    result
+}
+
+// Drawn over the form: the rules that end up shadowed, then the diff of the file about to be written.
+fn render_save_preview(frame: &mut Frame, save_plan: &SavePlan, title: &str, scroll: u16, area: Rect)
+{
+    let popup_area = Rect {
+        x: area.x + area.width / 10,
+        y: area.y + area.height / 10,
+        width: area.width - area.width / 5,
+        height: area.height - area.height / 5,
+    };
+
+    let mut lines = Vec::new();
+
+    for rule in &save_plan.shadowed_rules
+    {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "! {}",
+                t!(
+                    "save_preview_shadowed",
+                    name = rule.data.name.as_deref().unwrap_or("?"),
+                    file = rule.source_file.display()
+                )
+            ),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    if !save_plan.shadowed_rules.is_empty()
+    {
+        lines.push(Line::default());
+    }
+
+    let diff: String = save_plan.changes.iter().map(FileChange::unified_diff).collect();
+
+    if diff.is_empty()
+    {
+        lines.push(Line::from(Span::styled(
+            t!("save_preview_no_changes"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for diff_line in diff.lines()
+    {
+        let color = if diff_line.starts_with("+++") || diff_line.starts_with("---")
+        {
+            Color::White
+        }
+        else if diff_line.starts_with('+')
+        {
+            Color::Green
+        }
+        else if diff_line.starts_with('-')
+        {
+            Color::Red
+        }
+        else if diff_line.starts_with("@@")
+        {
+            Color::Cyan
+        }
+        else
+        {
+            Color::Gray
+        };
+
+        lines.push(Line::from(Span::styled(
+            diff_line.to_string(),
+            Style::default().fg(color),
+        )));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), popup_area);
+}
+
+// The views are drawn with dark terminal colors, other themes remap them once the frame is complete.
+fn apply_theme(buffer: &mut Buffer, theme: Theme)
+{
+    let remap: fn(Color) -> Color = match theme
+    {
+        Theme::Dark => return,
+        Theme::Light => |color| match color
+        {
+            Color::White => Color::Black,
+            Color::Gray => Color::DarkGray,
+            Color::DarkGray => Color::Gray,
+            Color::Yellow => Color::Rgb(150, 110, 0),
+            Color::Cyan => Color::Blue,
+            Color::Green => Color::Rgb(0, 120, 0),
+            other => other,
+        },
+        Theme::Monochrome => |_| Color::Reset,
+    };
+
+    for cell in buffer.content.iter_mut()
+    {
+        cell.fg = remap(cell.fg);
+        cell.bg = remap(cell.bg);
+    }
+}
+
+fn render_search(frame: &mut Frame, app: &App, process_service: &ProcessService, area: Rect)
 {
     let search_style = match app.input_mode
     {
         InputMode::Editing => Style::default().fg(Color::Yellow),
-        InputMode::Normal => Style::default().fg(Color::White),
-        InputMode::RuleForm => Style::default().fg(Color::DarkGray),
+        InputMode::Normal | InputMode::Jump => Style::default().fg(Color::White),
+        InputMode::RuleForm
+        | InputMode::ConfirmSave
+        | InputMode::ColumnPicker
+        | InputMode::BulkMenu
+        | InputMode::BulkInput
+        | InputMode::ConfirmBulk => Style::default().fg(Color::DarkGray),
     };
 
     let mut search_title = format!(
@@ -618,6 +1855,36 @@ fn render_search(frame: &mut Frame, app: &App, area: Rect)
         search_title.push_str(&t!("active_filter_enabled"));
     }
 
+    if app.search_mode == SearchMode::Fuzzy
+    {
+        search_title.push_str(&t!("fuzzy_search"));
+    }
+
+    if process_service.matching_mode() == MatchingMode::Discovery
+    {
+        search_title.push_str(&t!("discovery_mode"));
+    }
+
+    if let Some(captured_at) = process_service.snapshot_captured_at()
+    {
+        search_title.push_str(&t!("snapshot_mode", captured_at = captured_at));
+    }
+
+    if app.loading_rules || app.scanning_processes
+    {
+        search_title.push_str(&t!("refreshing"));
+    }
+
+    let search_style = match &app.search_error
+    {
+        Some(search_error) =>
+        {
+            search_title.push_str(&t!("query_error", error = search_error));
+            search_style.fg(Color::Red)
+        }
+        None => search_style,
+    };
+
     let search_text = Paragraph::new(app.search_query.as_str()).style(search_style).block(
         Block::default()
             .borders(Borders::ALL)
@@ -630,11 +1897,23 @@ fn render_search(frame: &mut Frame, app: &App, area: Rect)
 
 fn render_content(frame: &mut Frame, app: &mut App, process_service: &ProcessService, area: Rect)
 {
-    if app.input_mode == InputMode::RuleForm
+    if app.view == View::ProcessTree && !app.is_editing_rule()
+    {
+        render_process_tree(frame, app, process_service, area);
+    }
+    else if app.view == View::Dashboard && !app.is_editing_rule()
+    {
+        render_dashboard(frame, app, area);
+    }
+    else if app.view == View::Diagnostics && !app.is_editing_rule()
+    {
+        render_diagnostics(frame, app, area);
+    }
+    else if app.is_editing_rule()
     {
         let v_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(13), Constraint::Min(1)])
+            .constraints([Constraint::Length(FORM_FIELD_COUNT as u16 + 4), Constraint::Min(1)])
             .split(area);
 
         let h_chunks = Layout::default()
@@ -642,138 +1921,707 @@ fn render_content(frame: &mut Frame, app: &mut App, process_service: &ProcessSer
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(v_chunks[0]);
 
-        render_rule_form(frame, app, h_chunks[0]);
-        render_details(frame, app, process_service, h_chunks[1]);
-        render_process_list(frame, app, v_chunks[1]);
-    }
-    else
+        render_rule_form(frame, app, h_chunks[0]);
+        render_details(frame, app, process_service, h_chunks[1]);
+
+        if !app.field_options.is_empty()
+        {
+            render_field_options(frame, app, v_chunks[1]);
+        }
+        else
+        {
+            render_process_list(frame, app, v_chunks[1]);
+        }
+    }
+    else
+    {
+        let layout_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+            ])
+            .split(area);
+
+        render_rule_tree(frame, app, layout_chunks[0]);
+        render_list(frame, app, process_service, layout_chunks[1]);
+        render_details(frame, app, process_service, layout_chunks[2]);
+    }
+}
+
+fn render_rule_tree(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let filter_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+
+    let mut items = vec![ListItem::new(Line::from(Span::styled(
+        t!("tree_all_rules", count = app.all_rules.len()).to_string(),
+        if app.tree_filter.is_none()
+        {
+            filter_style
+        }
+        else
+        {
+            Style::default()
+        },
+    )))];
+
+    for node in app.visible_tree_nodes()
+    {
+        let marker = match node.kind
+        {
+            RuleTreeNodeKind::File => "  ",
+            _ if app.collapsed_nodes.contains(&node.path) => "▸ ",
+            _ => "▾ ",
+        };
+
+        let label_style = if app.tree_filter.as_ref() == Some(&node.path)
+        {
+            filter_style
+        }
+        else
+        {
+            match node.kind
+            {
+                RuleTreeNodeKind::Root => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                RuleTreeNodeKind::Directory => Style::default().fg(Color::Cyan),
+                RuleTreeNodeKind::File => Style::default(),
+            }
+        };
+
+        items.push(ListItem::new(Line::from(vec![
+            Span::raw(format!("{}{}", "  ".repeat(node.depth), marker)),
+            Span::styled(node.label(), label_style),
+            Span::styled(format!(" ({})", node.rule_count), Style::default().fg(Color::DarkGray)),
+        ])));
+    }
+
+    let border_style = if app.is_sidebar_active()
+    {
+        Style::default().fg(Color::Yellow)
+    }
+    else
+    {
+        Style::default()
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", t!("tree_title")))
+        .border_style(border_style);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+
+    frame.render_stateful_widget(list, area, &mut app.sidebar_state);
+}
+
+fn render_list(frame: &mut Frame, app: &mut App, process_service: &ProcessService, area: Rect)
+{
+    app.resize_page(area.height);
+
+    let total_items = app.filtered_rules.len();
+    let total_pages = app.pagination.page_count(total_items);
+
+    let page_items_data = &app.filtered_rules[app.pagination.page_range(total_items)];
+
+    let columns = app.visible_columns();
+
+    let header = Row::new(columns.iter().map(|column| {
+        let mut title = column_title(*column).into_owned();
+
+        if let Some(rule_sort) = app.rule_sort.filter(|rule_sort| rule_sort.column == *column)
+        {
+            title.push(if rule_sort.descending { '▼' } else { '▲' });
+        }
+
+        Cell::from(title)
+    }))
+    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = page_items_data
+        .iter()
+        .map(|rule| {
+            let original_name = rule.data.name.as_deref().unwrap_or("");
+            let is_active = !rule.shadowed && process_service.is_process_active(original_name);
+
+            let cells = columns.iter().map(|column| match column
+            {
+                RuleColumn::Name =>
+                {
+                    let mut name_display = rule
+                        .data
+                        .name
+                        .as_deref()
+                        .map(Cow::Borrowed)
+                        .unwrap_or_else(|| t!("unknown"));
+
+                    let name_style = if rule.shadowed
+                    {
+                        name_display.to_mut().push_str(" (Shadowed)");
+                        Style::default().fg(Color::DarkGray)
+                    }
+                    else if is_active
+                    {
+                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                    }
+                    else
+                    {
+                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                    };
+
+                    let highlights = app.search.highlight(original_name, app.search_mode);
+                    let mut spans = highlight_spans(&name_display, &highlights, name_style);
+
+                    if app.is_marked(rule)
+                    {
+                        spans.insert(
+                            0,
+                            Span::styled("* ", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+                        );
+                    }
+
+                    Cell::from(Line::from(spans))
+                }
+                RuleColumn::Type => Cell::from(rule.data.rule_type.as_deref().unwrap_or("-")),
+                RuleColumn::Nice => Cell::from(optional_value(rule.data.nice)),
+                RuleColumn::LatencyNice => Cell::from(optional_value(rule.data.latency_nice)),
+                RuleColumn::Sched => Cell::from(rule.data.sched.as_deref().unwrap_or("-")),
+                RuleColumn::IoClass => Cell::from(rule.data.ioclass.as_deref().unwrap_or("-")),
+                RuleColumn::OomScoreAdj => Cell::from(optional_value(rule.data.oom_score_adj)),
+                RuleColumn::Cgroup => Cell::from(rule.data.cgroup.as_deref().unwrap_or("-")),
+                RuleColumn::Category => Cell::from(rule.category()).style(Style::default().fg(Color::Blue)),
+                RuleColumn::Active if is_active => Cell::from("●").style(Style::default().fg(Color::Green)),
+                RuleColumn::Active => Cell::from(""),
+            });
+
+            Row::new(cells)
+        })
+        .collect();
+
+    let mut list_title = format!(
+        " {} ",
+        t!(
+            "rules_page",
+            current = app.pagination.current_page + 1,
+            total = total_pages
+        )
+    );
+
+    if let Some(tree_filter) = &app.tree_filter
+    {
+        list_title.push_str(&format!("{} ", t!("rules_in_path", path = tree_filter.display())));
+    }
+
+    if !app.marked_rules.is_empty()
+    {
+        list_title.push_str(&format!("| {} ", t!("rules_marked", count = app.marked_rules.len())));
+    }
+
+    let mut list_block = Block::default().borders(Borders::ALL).title(list_title);
+
+    if !app.rules_errors.is_empty()
+    {
+        let error_msg = format!(" {} ", t!("error_loading_files", count = app.rules_errors.len()));
+
+        list_block = list_block.title_bottom(
+            Line::from(error_msg)
+                .style(Style::default().fg(Color::Red))
+                .alignment(Alignment::Left),
+        );
+    }
+
+    let widths = columns.iter().map(|column| column_width(*column));
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(list_block)
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    frame.render_stateful_widget(table, area, &mut app.list_state);
+}
+
+fn column_title(column: RuleColumn) -> Cow<'static, str>
+{
+    match column
+    {
+        RuleColumn::Name => t!("column_name"),
+        RuleColumn::Type => t!("column_type"),
+        RuleColumn::Nice => t!("column_nice"),
+        RuleColumn::LatencyNice => t!("column_latency_nice"),
+        RuleColumn::Sched => t!("column_sched"),
+        RuleColumn::IoClass => t!("column_ioclass"),
+        RuleColumn::OomScoreAdj => t!("column_oom_score_adj"),
+        RuleColumn::Cgroup => t!("column_cgroup"),
+        RuleColumn::Category => t!("column_category"),
+        RuleColumn::Active => t!("column_active"),
+    }
+}
+
+fn column_width(column: RuleColumn) -> Constraint
+{
+    match column
+    {
+        RuleColumn::Name => Constraint::Min(12),
+        RuleColumn::Nice | RuleColumn::OomScoreAdj => Constraint::Length(5),
+        RuleColumn::LatencyNice | RuleColumn::Sched | RuleColumn::Active => Constraint::Length(7),
+        RuleColumn::IoClass => Constraint::Length(11),
+        RuleColumn::Type | RuleColumn::Cgroup | RuleColumn::Category => Constraint::Length(12),
+    }
+}
+
+fn optional_value(value: Option<i32>) -> String
+{
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+fn render_bulk_menu(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let popup_area = Rect {
+        x: area.x + area.width.saturating_sub(50) / 2,
+        y: area.y + area.height.saturating_sub(9) / 2,
+        width: area.width.min(50),
+        height: area.height.min(9),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", t!("bulk_title", count = app.marked_rules.len())))
+        .title_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(BULK_ACTIONS.len() as u16 + 1), Constraint::Min(1)])
+        .split(inner_area);
+
+    let items: Vec<ListItem> = BULK_ACTIONS.iter().map(|action| ListItem::new(t!(*action))).collect();
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    frame.render_stateful_widget(list, chunks[0], &mut app.bulk_menu_state);
+
+    if app.input_mode == InputMode::BulkInput
+    {
+        let prompt = if app.bulk_menu_state.selected() == Some(BULK_MOVE)
+        {
+            format!("{}: ", t!("bulk_prompt_file"))
+        }
+        else
+        {
+            format!("{}: ", t!("bulk_prompt_set"))
+        };
+
+        let input = Line::from(vec![
+            Span::styled(prompt, Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}_", app.bulk_input)),
+        ]);
+
+        frame.render_widget(Paragraph::new(input), chunks[1]);
+    }
+}
+
+fn render_column_picker(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let popup_area = Rect {
+        x: area.x + area.width.saturating_sub(30) / 2,
+        y: area.y + area.height.saturating_sub(14) / 2,
+        width: area.width.min(30),
+        height: area.height.min(14),
+    };
+
+    let items: Vec<ListItem> = RuleColumn::ALL
+        .iter()
+        .map(|column| {
+            let checkbox = if app.hidden_columns.contains(column)
+            {
+                "[ ]"
+            }
+            else
+            {
+                "[x]"
+            };
+
+            ListItem::new(Line::from(format!("{} {}", checkbox, column_title(*column))))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", t!("columns_title")))
+        .title_style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_stateful_widget(list, popup_area, &mut app.column_picker_state);
+}
+
+// Splits the text into runs so the matched characters stand out.
+fn highlight_spans<'a>(text: &str, highlights: &[usize], style: Style) -> Vec<Span<'a>>
+{
+    let highlight_style = style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_highlighted = false;
+
+    for (index, character) in text.chars().enumerate()
+    {
+        let highlighted = highlights.binary_search(&index).is_ok();
+
+        if highlighted != run_highlighted && !run.is_empty()
+        {
+            let run_style = if run_highlighted { highlight_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+
+        run_highlighted = highlighted;
+        run.push(character);
+    }
+
+    if !run.is_empty()
+    {
+        let run_style = if run_highlighted { highlight_style } else { style };
+        spans.push(Span::styled(run, run_style));
+    }
+
+    spans
+}
+
+fn render_process_tree(frame: &mut Frame, app: &mut App, process_service: &ProcessService, area: Rect)
+{
+    let mut shown_process_ids = Vec::new();
+
+    let items: Vec<ListItem> = app
+        .process_tree
+        .iter()
+        .filter(|node| {
+            !app.tree_matched_only
+                || app.rule_matches.contains_key(&node.process_id)
+                || app.inherited_rules.contains_key(&node.process_id)
+                || app.has_matched_descendant.contains(&node.process_id)
+        })
+        .map(|node| {
+            shown_process_ids.push(node.process_id);
+
+            let direct_match = app.rule_matches.get(&node.process_id);
+            let applied_rule = direct_match
+                .map(|(rule, _)| rule)
+                .or_else(|| app.inherited_rules.get(&node.process_id));
+
+            let mut spans = vec![
+                Span::raw("  ".repeat(node.depth)),
+                Span::styled(
+                    if node.depth > 0 { "└ " } else { "" },
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(format!("{} ", node.process_id), Style::default().fg(Color::DarkGray)),
+                Span::styled(node.name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ];
+
+            match (direct_match, applied_rule)
+            {
+                (Some((rule, match_kind)), _) =>
+                {
+                    let match_style = if match_kind.is_weak()
+                    {
+                        Style::default().fg(Color::Yellow)
+                    }
+                    else
+                    {
+                        Style::default().fg(Color::Green)
+                    };
+
+                    spans.push(Span::styled(
+                        format!(
+                            " ◆ {} ({})",
+                            rule.data.name.as_deref().unwrap_or(""),
+                            t!("matched_by", kind = match_kind)
+                        ),
+                        match_style,
+                    ));
+                }
+                (None, Some(rule)) => spans.push(Span::styled(
+                    format!(
+                        " ↳ {}",
+                        t!("inherits_from", rule = rule.data.name.as_deref().unwrap_or(""))
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )),
+                (None, None) =>
+                {}
+            }
+
+            if let Some(process_info) = process_service.get_process_info(node.process_id)
+            {
+                let want_nice = applied_rule.and_then(|rule| rule.data.nice);
+                let nice_style = match (want_nice, process_info.nice.value())
+                {
+                    (Some(want), Some(have)) if want == *have => Style::default().fg(Color::Green),
+                    (Some(_), Some(_)) => Style::default().fg(Color::Red),
+                    _ => Style::default().fg(Color::DarkGray),
+                };
+
+                spans.push(Span::styled(format!("  nice {}", process_info.nice), nice_style));
+                spans.push(Span::styled(
+                    format!(" | {} | {}", process_info.sched_policy, process_info.ioclass),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    app.tree_len = items.len();
+
+    let mut tree_title = format!(" {} ", t!("process_tree_title", count = app.process_tree.len()));
+
+    if app.tree_matched_only
     {
-        let layout_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(area);
+        tree_title.push_str(&t!("tree_matched_only"));
+    }
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(tree_title))
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
 
-        render_list(frame, app, process_service, layout_chunks[0]);
-        render_details(frame, app, process_service, layout_chunks[1]);
+    match app.tree_state.selected()
+    {
+        _ if app.tree_len == 0 => app.tree_state.select(None),
+        Some(selected) if selected >= app.tree_len => app.tree_state.select(Some(app.tree_len - 1)),
+        None => app.tree_state.select(Some(0)),
+        _ =>
+        {}
     }
+
+    frame.render_stateful_widget(list, area, &mut app.tree_state);
+
+    // Rows inside the borders, as scrolled by the list.
+    app.visible_tree_processes = shown_process_ids
+        .into_iter()
+        .skip(app.tree_state.offset())
+        .take(area.height.saturating_sub(2) as usize)
+        .collect();
 }
 
-fn render_list(frame: &mut Frame, app: &mut App, process_service: &ProcessService, area: Rect)
+// Every problem found while loading or saving rules, with the place it points at.
+fn render_diagnostics(frame: &mut Frame, app: &mut App, area: Rect)
 {
-    let total_items = app.filtered_rules.len();
-    let total_pages = if total_items > 0
-    {
-        (total_items as f64 / app.items_per_page as f64).ceil() as usize
-    }
-    else
-    {
-        1
-    };
+    let diagnostics = app.diagnostics();
 
-    let start_index = app.current_page * app.items_per_page;
-    let end_index = (start_index + app.items_per_page).min(total_items);
+    let title = format!(" {} ", t!("diagnostics_title", count = diagnostics.len()));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
 
-    let page_items_data = if total_items > 0 && start_index < total_items
+    if diagnostics.is_empty()
     {
-        &app.filtered_rules[start_index..end_index]
+        let paragraph = Paragraph::new(t!("no_diagnostics").to_string())
+            .style(Style::default().fg(Color::Green))
+            .block(block);
+        frame.render_widget(paragraph, area);
+
+        return;
     }
-    else
-    {
-        &[]
-    };
 
-    let items: Vec<ListItem> = page_items_data
+    let items: Vec<ListItem> = diagnostics
         .iter()
-        .map(|rule| {
-            let category = rule
-                .source_file
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("root");
+        .map(|diagnostic| {
+            let location = match (&diagnostic.file, diagnostic.line)
+            {
+                (Some(file), Some(line)) => format!("{}:{}", file.display(), line),
+                (Some(file), None) => file.display().to_string(),
+                (None, _) => "-".to_string(),
+            };
 
-            let original_name = rule.data.name.as_deref().unwrap_or("");
-            let mut name_display = rule
-                .data
-                .name
-                .as_deref()
-                .map(Cow::Borrowed)
-                .unwrap_or_else(|| t!("unknown").into());
+            ListItem::new(vec![
+                Line::from(Span::styled(
+                    location,
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(
+                    format!("  {}", diagnostic.message),
+                    Style::default().fg(Color::Red),
+                )),
+            ])
+        })
+        .collect();
 
-            if rule.shadowed
-            {
-                name_display.to_mut().push_str(" (Shadowed)");
-            }
+    if app
+        .diagnostics_state
+        .selected()
+        .is_none_or(|selected| selected >= items.len())
+    {
+        app.diagnostics_state.select(Some(0));
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
 
-            let is_active = process_service.is_process_active(original_name);
+    frame.render_stateful_widget(list, area, &mut app.diagnostics_state);
+}
 
-            let name_style = if rule.shadowed
-            {
-                Style::default().fg(Color::DarkGray)
-            }
-            else if is_active
-            {
-                name_display.to_mut().push_str(" [ACTIVE]");
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
-            }
-            else
-            {
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-            };
+fn render_dashboard(frame: &mut Frame, app: &App, area: Rect)
+{
+    let statistics = &app.statistics;
 
-            let rule_type = rule.data.rule_type.as_deref().unwrap_or("-");
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .split(area);
 
-            let content = Line::from(vec![
-                Span::styled(format!("[{:^7}] ", category), Style::default().fg(Color::Blue)),
-                Span::styled(name_display, name_style),
-                Span::styled(format!(" ({}) ", rule_type), Style::default().fg(Color::White)),
-            ]);
+    let summary = Line::from(vec![
+        Span::raw(format!(" {}: ", t!("stats_total"))),
+        Span::styled(statistics.total_rules.to_string(), Style::default().fg(Color::Green)),
+        Span::raw(format!(" | {}: ", t!("stats_active"))),
+        Span::styled(statistics.active_rules.to_string(), Style::default().fg(Color::Green)),
+        Span::raw(format!(" | {}: ", t!("stats_shadowed"))),
+        Span::styled(
+            statistics.shadowed_rules.to_string(),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::raw(format!(" | {}: ", t!("stats_parse_errors"))),
+        Span::styled(statistics.parse_errors.to_string(), Style::default().fg(Color::Red)),
+    ]);
+
+    frame.render_widget(
+        Paragraph::new(summary).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", t!("stats_title"))),
+        ),
+        rows[0],
+    );
 
-            ListItem::new(content)
+    let top_columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+        ])
+        .split(rows[1]);
+
+    let bottom_columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(34),
+            Constraint::Percentage(33),
+            Constraint::Percentage(33),
+        ])
+        .split(rows[2]);
+
+    let file_counts: Vec<(String, usize)> = statistics
+        .rules_per_file
+        .iter()
+        .map(|(path, count)| {
+            let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+            (file_name.into_owned(), *count)
         })
         .collect();
 
-    let list_title = format!(
-        " {} ",
-        t!("rules_page", current = app.current_page + 1, total = total_pages)
-    );
-
-    let mut list_block = Block::default().borders(Borders::ALL).title(list_title);
+    let histograms = [
+        (
+            t!("stats_per_category"),
+            counts_to_strings(&statistics.rules_per_category),
+            top_columns[0],
+        ),
+        (t!("stats_per_file"), file_counts, top_columns[1]),
+        (
+            t!("stats_per_type"),
+            counts_to_strings(&statistics.rules_per_type),
+            top_columns[2],
+        ),
+        (
+            t!("stats_nice"),
+            counts_to_strings(&statistics.nice_histogram),
+            bottom_columns[0],
+        ),
+        (
+            t!("stats_latency_nice"),
+            counts_to_strings(&statistics.latency_nice_histogram),
+            bottom_columns[1],
+        ),
+        (
+            t!("stats_oom_score_adj"),
+            counts_to_strings(&statistics.oom_score_adj_histogram),
+            bottom_columns[2],
+        ),
+    ];
 
-    if !app.rules_errors.is_empty()
+    for (title, counts, histogram_area) in histograms
     {
-        let error_msg = format!(" {} ", t!("error_loading_files", count = app.rules_errors.len()));
-
-        list_block = list_block.title_bottom(
-            Line::from(error_msg)
-                .style(Style::default().fg(Color::Red))
-                .alignment(Alignment::Left),
-        );
+        render_histogram(frame, &title, &counts, histogram_area);
     }
+}
 
-    let list = List::new(items)
-        .block(list_block)
-        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
-        .highlight_symbol(">> ");
+fn counts_to_strings<K: ToString>(counts: &std::collections::BTreeMap<K, usize>) -> Vec<(String, usize)>
+{
+    counts.iter().map(|(key, count)| (key.to_string(), *count)).collect()
+}
+
+fn render_histogram(frame: &mut Frame, title: &str, counts: &[(String, usize)], area: Rect)
+{
+    let label_width = counts.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+    let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(1);
+    let count_width = max_count.to_string().len();
 
-    frame.render_stateful_widget(list, area, &mut app.list_state);
+    // Borders, the label, the count and the spaces between them.
+    let bar_width = (area.width as usize)
+        .saturating_sub(label_width + count_width + 4)
+        .max(1);
+
+    let lines: Vec<Line> = counts
+        .iter()
+        .map(|(key, count)| {
+            let bar_length = (count * bar_width).div_ceil(max_count).min(bar_width);
+
+            Line::from(vec![
+                Span::raw(format!("{:>width$} ", key, width = label_width)),
+                Span::styled("█".repeat(bar_length), Style::default().fg(Color::Cyan)),
+                Span::raw(format!(" {}", count)),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(" {} ", title))),
+        area,
+    );
 }
 
 fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService, area: Rect)
 {
-    let (target_rule, source_file, context_comment, shadowed) = if app.input_mode == InputMode::RuleForm
+    let (target_rule, source_file, context_comment, shadowed) = if app.is_editing_rule()
     {
         (&app.editing_rule, None, None, false)
     }
-    else if let Some(visual_idx) = app.list_state.selected()
+    else if app.list_state.selected().is_some()
     {
-        let start_index = app.current_page * app.items_per_page;
-        let real_index = start_index + visual_idx;
-        if let Some(rule) = app.filtered_rules.get(real_index)
+        if let Some(rule) = app.selected_rule()
         {
             (
                 &rule.data,
-                Some(rule.source_file.to_string_lossy()),
+                Some(format!("{}:{}", rule.source_file.display(), rule.line_number)),
                 rule.context_comment.as_deref(),
                 rule.shadowed,
             )
@@ -814,35 +2662,49 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
     let running_processes = process_service.get_process_infos(rule_name);
     let current_proc = running_processes.first();
 
-    let compare_i32 = |target: Option<i32>, actual: Option<i32>| -> Span {
+    let compare_i32 = |target: Option<i32>, actual: Option<&AttributeReading<i32>>| -> Span {
         match (target, actual)
         {
-            (Some(t), Some(a)) if t == a =>
+            (Some(t), Some(AttributeReading::Value(a))) if t == *a =>
             {
                 Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Green))
             }
-            (Some(_), Some(a)) => Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Red)),
-            (None, Some(a)) => Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::DarkGray)),
+            (Some(_), Some(AttributeReading::Value(a))) =>
+            {
+                Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Red))
+            }
+            (None, Some(AttributeReading::Value(a))) =>
+            {
+                Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::DarkGray))
+            }
+            (_, Some(reading)) => unreadable_reading_span(reading),
             _ => Span::raw(""),
         }
     };
 
-    let compare_str = |target: &Option<String>, actual: Option<String>| -> Span {
+    let compare_str = |target: &Option<String>, actual: Option<&AttributeReading<String>>| -> Span {
         match (target, actual)
         {
-            (Some(t), Some(ref a)) if t.eq_ignore_ascii_case(a) =>
+            (Some(t), Some(AttributeReading::Value(a))) if t.eq_ignore_ascii_case(a) =>
             {
                 Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Green))
             }
-            (Some(_), Some(ref a)) => Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Red)),
-            (None, Some(ref a)) => Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::DarkGray)),
+            (Some(_), Some(AttributeReading::Value(a))) =>
+            {
+                Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::Red))
+            }
+            (None, Some(AttributeReading::Value(a))) =>
+            {
+                Span::styled(format!(" (Current: {})", a), Style::default().fg(Color::DarkGray))
+            }
+            (_, Some(reading)) => unreadable_reading_span(reading),
             _ => Span::raw(""),
         }
     };
 
     let mut lines = Vec::new();
 
-    if app.input_mode == InputMode::RuleForm
+    if app.is_editing_rule()
     {
         lines.push(Line::from(Span::styled(
             " --- PREVIEW --- ",
@@ -867,6 +2729,31 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
             ),
             Style::default().fg(Color::Green),
         )));
+
+        if let Some(match_kind) = process_service.explain_match(rule_name, running_processes[0].process_id)
+        {
+            let match_style = if match_kind.is_weak()
+            {
+                Style::default().fg(Color::Yellow)
+            }
+            else
+            {
+                Style::default().fg(Color::DarkGray)
+            };
+
+            lines.push(Line::from(Span::styled(
+                format!("Match: {}", t!("matched_by", kind = match_kind)),
+                match_style,
+            )));
+
+            if match_kind.is_weak()
+            {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ {}", t!("weak_match_warning")),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+        }
     }
     else
     {
@@ -894,31 +2781,67 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
         lines.push(Line::from(vec![
             Span::raw("Nice: "),
             Span::styled(nice.to_string(), Style::default().fg(Color::Yellow)),
-            compare_i32(Some(nice), current_proc.and_then(|p| p.nice)),
+            compare_i32(Some(nice), current_proc.map(|p| &p.nice)),
         ]));
     }
     else if let Some(p) = current_proc
     {
-        lines.push(Line::from(vec![Span::raw("Nice: -"), compare_i32(None, p.nice)]));
+        lines.push(Line::from(vec![Span::raw("Nice: -"), compare_i32(None, Some(&p.nice))]));
     }
 
-    if let Some(lat) = target_rule.latency_nice
+    if let Some(p) = current_proc
+        && let AttributeReading::Value(autogroup) = &p.autogroup
     {
+        let autogroup_overrides_nice = process_service.autogroup_overrides_nice(target_rule.nice, p);
+        let autogroup_style = if autogroup_overrides_nice
+        {
+            Style::default().fg(Color::Yellow)
+        }
+        else
+        {
+            Style::default().fg(Color::DarkGray)
+        };
+
         lines.push(Line::from(vec![
-            Span::raw("Nice latency: "),
-            Span::styled(lat.to_string(), Style::default()),
-            compare_i32(Some(lat), current_proc.and_then(|p| p.latency_nice)),
+            Span::raw("Autogroup: "),
+            Span::styled(format!("{} (nice {})", autogroup.id, autogroup.nice), autogroup_style),
         ]));
+
+        if autogroup_overrides_nice
+        {
+            lines.push(Line::from(Span::styled(
+                format!("  ⚠ {}", t!("autogroup_nice_warning")),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+    }
+
+    let capabilities = process_service.capabilities();
+    let latency_nice_supported = capabilities.is_none_or(|c| c.latency_nice);
+    let ioclass_supported = capabilities.is_none_or(|c| c.supports_ioclass());
+    let cgroup_supported = capabilities.is_none_or(|c| c.supports_cgroup_cpu());
+
+    if let Some(lat) = target_rule.latency_nice
+    {
+        lines.push(mark_if_unsupported(
+            Line::from(vec![
+                Span::raw("Nice latency: "),
+                Span::styled(lat.to_string(), Style::default()),
+                compare_i32(Some(lat), current_proc.map(|p| &p.latency_nice)),
+            ]),
+            latency_nice_supported,
+        ));
     }
     else if let Some(p) = current_proc
+        && p.latency_nice.is_value()
     {
-        if let Some(val) = p.latency_nice
-        {
-            lines.push(Line::from(vec![
+        lines.push(mark_if_unsupported(
+            Line::from(vec![
                 Span::raw("Nice latency: -"),
-                compare_i32(None, Some(val)),
-            ]));
-        }
+                compare_i32(None, Some(&p.latency_nice)),
+            ]),
+            latency_nice_supported,
+        ));
     }
 
     if let Some(sched) = &target_rule.sched
@@ -926,14 +2849,14 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
         lines.push(Line::from(vec![
             Span::raw("Scheduling policy: "),
             Span::styled(sched, Style::default()),
-            compare_str(&Some(sched.clone()), current_proc.and_then(|p| p.sched_policy.clone())),
+            compare_str(&Some(sched.clone()), current_proc.map(|p| &p.sched_policy)),
         ]));
     }
     else if let Some(p) = current_proc
     {
         lines.push(Line::from(vec![
             Span::raw("Scheduling policy: -"),
-            compare_str(&None, p.sched_policy.clone()),
+            compare_str(&None, Some(&p.sched_policy)),
         ]));
     }
 
@@ -942,34 +2865,35 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
         lines.push(Line::from(vec![
             Span::raw("Static priority: "),
             Span::styled(rtprio.to_string(), Style::default()),
-            compare_i32(Some(rtprio), current_proc.and_then(|p| p.rtprio)),
+            compare_i32(Some(rtprio), current_proc.map(|p| &p.rtprio)),
         ]));
     }
     else if let Some(p) = current_proc
+        && p.rtprio.is_value()
     {
-        if let Some(val) = p.rtprio
-        {
-            lines.push(Line::from(vec![
-                Span::raw("Static priority: -"),
-                compare_i32(None, Some(val)),
-            ]));
-        }
+        lines.push(Line::from(vec![
+            Span::raw("Static priority: -"),
+            compare_i32(None, Some(&p.rtprio)),
+        ]));
     }
 
     if let Some(ioclass) = &target_rule.ioclass
     {
-        lines.push(Line::from(vec![
-            Span::raw("IO class: "),
-            Span::styled(ioclass, Style::default()),
-            compare_str(&Some(ioclass.clone()), current_proc.and_then(|p| p.ioclass.clone())),
-        ]));
+        lines.push(mark_if_unsupported(
+            Line::from(vec![
+                Span::raw("IO class: "),
+                Span::styled(ioclass, Style::default()),
+                compare_str(&Some(ioclass.clone()), current_proc.map(|p| &p.ioclass)),
+            ]),
+            ioclass_supported,
+        ));
     }
     else if let Some(p) = current_proc
     {
-        lines.push(Line::from(vec![
-            Span::raw("IO class: -"),
-            compare_str(&None, p.ioclass.clone()),
-        ]));
+        lines.push(mark_if_unsupported(
+            Line::from(vec![Span::raw("IO class: -"), compare_str(&None, Some(&p.ioclass))]),
+            ioclass_supported,
+        ));
     }
 
     if let Some(oom_score_adj) = target_rule.oom_score_adj
@@ -977,59 +2901,126 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
         lines.push(Line::from(vec![
             Span::raw("Out of memory killer score: "),
             Span::styled(oom_score_adj.to_string(), Style::default()),
-            compare_i32(Some(oom_score_adj), current_proc.and_then(|p| p.oom_score_adj)),
+            compare_i32(Some(oom_score_adj), current_proc.map(|p| &p.oom_score_adj)),
         ]));
     }
     else if let Some(p) = current_proc
     {
         lines.push(Line::from(vec![
             Span::raw("Out of memory killer score: -"),
-            compare_i32(None, p.oom_score_adj),
+            compare_i32(None, Some(&p.oom_score_adj)),
         ]));
     }
 
     if let Some(cgroup) = &target_rule.cgroup
     {
-        let current_cgroup = current_proc.and_then(|p| p.cgroup.clone());
-
-        let style = if current_cgroup
-            .as_ref()
-            .map(|c| c.eq_ignore_ascii_case(cgroup))
-            .unwrap_or(false)
-        {
-            Style::default().fg(Color::Green)
-        }
-        else if current_cgroup.is_some()
-        {
-            Style::default().fg(Color::Red)
-        }
-        else
+        let current_display = match current_proc.map(|p| &p.cgroup)
         {
-            Style::default().fg(Color::DarkGray)
-        };
+            Some(AttributeReading::Value(current_cgroup)) =>
+            {
+                let style = if current_cgroup.eq_ignore_ascii_case(cgroup)
+                {
+                    Style::default().fg(Color::Green)
+                }
+                else
+                {
+                    Style::default().fg(Color::Red)
+                };
 
-        let current_display = current_cgroup
-            .map(|c| format!(" (Current: {})", ProcessService::shorten_cgroup(&c)))
-            .unwrap_or_default();
+                Span::styled(
+                    format!(" (Current: {})", ProcessService::shorten_cgroup(current_cgroup)),
+                    style,
+                )
+            }
+            Some(reading) => unreadable_reading_span(reading),
+            None => Span::raw(""),
+        };
 
-        lines.push(Line::from(vec![
-            Span::raw("Cgroup: "),
-            Span::styled(ProcessService::shorten_cgroup(cgroup), Style::default()),
-            Span::styled(current_display, style),
-        ]));
+        lines.push(mark_if_unsupported(
+            Line::from(vec![
+                Span::raw("Cgroup: "),
+                Span::styled(ProcessService::shorten_cgroup(cgroup), Style::default()),
+                current_display,
+            ]),
+            cgroup_supported,
+        ));
     }
     else if let Some(p) = current_proc
+        && let AttributeReading::Value(cgroup) = &p.cgroup
     {
-        if let Some(cgroup) = &p.cgroup
-        {
-            lines.push(Line::from(vec![
+        lines.push(mark_if_unsupported(
+            Line::from(vec![
                 Span::raw("Cgroup: -"),
                 Span::styled(
                     format!(" (Current: {})", ProcessService::shorten_cgroup(cgroup)),
                     Style::default().fg(Color::DarkGray),
                 ),
-            ]));
+            ]),
+            cgroup_supported,
+        ));
+    }
+
+    if let Some(p) = current_proc
+        && let Some(cgroup_resources) = process_service.get_cgroup_resources(p)
+    {
+        let cpu_quota = target_rule
+            .cgroup
+            .as_deref()
+            .and_then(|cgroup_name| RuleService::find_cgroup_definition(&app.cgroup_definitions, cgroup_name))
+            .and_then(|definition| definition.cpu_quota);
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            t!("cgroup_limits"),
+            Style::default().add_modifier(Modifier::UNDERLINED),
+        )));
+
+        let mut cpu_max_spans = vec![Span::raw("cpu.max: "), reading_span(&cgroup_resources.cpu_max)];
+
+        if let Some(percent) = cgroup_resources.cpu_quota_percent()
+        {
+            cpu_max_spans.push(Span::raw(format!(
+                " ({:.0}% of {} CPUs)",
+                percent, cgroup_resources.online_cpus
+            )));
+        }
+
+        if let Some(cpu_quota) = cpu_quota
+            && cgroup_resources.cpu_max.is_value()
+        {
+            let quota_style = if cgroup_resources.enforces_cpu_quota(cpu_quota)
+            {
+                Style::default().fg(Color::Green)
+            }
+            else
+            {
+                Style::default().fg(Color::Red)
+            };
+
+            cpu_max_spans.push(Span::styled(format!(" (CPUQuota: {}%)", cpu_quota), quota_style));
         }
+
+        lines.push(Line::from(cpu_max_spans));
+        lines.push(Line::from(vec![
+            Span::raw("cpu.weight: "),
+            reading_span(&cgroup_resources.cpu_weight),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("memory.max: "),
+            reading_span(&cgroup_resources.memory_max),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("io.weight: "),
+            reading_span(&cgroup_resources.io_weight),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("Pressure (avg10): cpu "),
+            reading_span(&cgroup_resources.cpu_pressure),
+            Span::raw(", memory "),
+            reading_span(&cgroup_resources.memory_pressure),
+            Span::raw(", io "),
+            reading_span(&cgroup_resources.io_pressure),
+        ]));
     }
 
     if let Some(src) = source_file
@@ -1070,7 +3061,7 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
         }
         else
         {
-            required_lines += (line_width + inner_width - 1) / inner_width;
+            required_lines += line_width.div_ceil(inner_width);
         }
     }
 
@@ -1101,28 +3092,86 @@ fn render_details(frame: &mut Frame, app: &App, process_service: &ProcessService
     frame.render_widget(details, area);
 }
 
+fn mark_if_unsupported(line: Line<'_>, supported: bool) -> Line<'_>
+{
+    if supported
+    {
+        return line;
+    }
+
+    let mut spans: Vec<Span> = line
+        .spans
+        .into_iter()
+        .map(|span| span.style(Style::default().fg(Color::DarkGray)))
+        .collect();
+
+    spans.push(Span::styled(
+        format!(" [{}]", t!("not_supported_here")),
+        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+    ));
+
+    Line::from(spans)
+}
+
+fn reading_span<T: std::fmt::Display>(reading: &AttributeReading<T>) -> Span<'static>
+{
+    match reading
+    {
+        AttributeReading::Value(value) => Span::raw(value.to_string()),
+        _ =>
+        {
+            let span = unreadable_reading_span(reading);
+            Span::styled(span.content.trim_start().to_string(), span.style)
+        }
+    }
+}
+
+fn unreadable_reading_span<T>(reading: &AttributeReading<T>) -> Span<'static>
+{
+    match reading
+    {
+        AttributeReading::Value(_) => Span::raw(""),
+        AttributeReading::Unsupported => Span::styled(
+            " (Unsupported)",
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        ),
+        AttributeReading::PermissionDenied => Span::styled(" (Permission denied)", Style::default().fg(Color::Yellow)),
+        AttributeReading::Gone => Span::styled(" (Process gone)", Style::default().fg(Color::DarkGray)),
+    }
+}
+
 fn render_help(frame: &mut Frame, app: &App, area: Rect)
 {
     let help_text = match app.input_mode
     {
         InputMode::Editing => Line::from(format!(" {} ", t!("help_editing"))),
+        InputMode::Normal if app.is_sidebar_active() => Line::from(format!(" {} ", t!("help_sidebar"))),
+        InputMode::Normal if app.view == View::ProcessTree => Line::from(format!(" {} ", t!("help_tree"))),
+        InputMode::Normal if app.view == View::Dashboard => Line::from(format!(" {} ", t!("help_dashboard"))),
+        InputMode::Normal if app.view == View::Diagnostics => Line::from(format!(" {} ", t!("help_diagnostics"))),
         InputMode::Normal =>
         {
             let base = format!(" {} ", t!("help_normal"));
             if app.is_root
             {
-                Line::from(format!("{}| n: New Rule | e: Edit Rule ", base))
+                Line::from(base)
             }
             else
             {
                 Line::from(vec![
                     Span::raw(base),
                     Span::raw("| "),
-                    Span::styled("Read-only mode ", Style::default().fg(Color::Yellow)),
+                    Span::styled(format!("{} ", t!("read_only_mode")), Style::default().fg(Color::Yellow)),
                 ])
             }
         }
-        InputMode::RuleForm => Line::from(" Esc: Cancel | Tab: Next | Enter: Select/Next | Ctrl+S: Save "),
+        InputMode::RuleForm => Line::from(" Esc: Cancel | Tab: Next | Enter: Select/Next | ←/→: Cycle | Ctrl+S: Save "),
+        InputMode::ConfirmSave => Line::from(" y/Enter: Write | n/Esc: Back to Form | ↑/↓: Scroll "),
+        InputMode::ColumnPicker => Line::from(format!(" {} ", t!("help_columns"))),
+        InputMode::BulkMenu => Line::from(format!(" {} ", t!("help_bulk_menu"))),
+        InputMode::BulkInput => Line::from(format!(" {} ", t!("help_bulk_input"))),
+        InputMode::ConfirmBulk => Line::from(format!(" {} ", t!("help_confirm_bulk"))),
+        InputMode::Jump => Line::from(format!(" {} ", t!("help_jump", prefix = app.jump_prefix))),
     };
 
     let help = Paragraph::new(help_text).style(Style::default().fg(Color::DarkGray));
@@ -1154,6 +3203,7 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
+                Constraint::Length(1),
                 Constraint::Min(0),
             ]
             .as_ref(),
@@ -1170,11 +3220,15 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
         "IO Class",
         "OOM Score Adj",
         "Cgroup",
+        "Target File",
     ];
 
+    let field_errors = app.field_errors();
+
     for (i, field_name) in fields.iter().enumerate()
     {
         let is_selected = i == app.editing_field_index;
+        let is_invalid = field_errors[i].is_some();
 
         let value = if is_selected
         {
@@ -1185,6 +3239,15 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
             app.get_field_value(i)
         };
 
+        let (value, value_fg) = if i == TARGET_FILE_FIELD && value.is_empty()
+        {
+            ("(new file in custom rules directory)".to_string(), Color::DarkGray)
+        }
+        else
+        {
+            (value, Color::White)
+        };
+
         let (prefix, bg_style, label_fg) = if is_selected
         {
             (">> ", Style::default().bg(Color::DarkGray), Color::Yellow)
@@ -1194,13 +3257,15 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
             ("   ", Style::default(), Color::Cyan)
         };
 
+        let label_fg = if is_invalid { Color::Red } else { label_fg };
+
         let value_style = if is_selected
         {
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            Style::default().fg(value_fg).add_modifier(Modifier::BOLD)
         }
         else
         {
-            Style::default().fg(Color::White)
+            Style::default().fg(value_fg)
         };
 
         let line = Line::from(vec![
@@ -1212,6 +3277,10 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
                     .add_modifier(if is_selected { Modifier::BOLD } else { Modifier::empty() }),
             ),
             Span::styled(value, value_style),
+            Span::styled(
+                if is_invalid { " ✗" } else { "" },
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
         ]);
 
         if i < layout.len()
@@ -1219,6 +3288,36 @@ fn render_rule_form(frame: &mut Frame, app: &mut App, area: Rect)
             frame.render_widget(Paragraph::new(line).style(bg_style), layout[i]);
         }
     }
+
+    // Below the fields: what is wrong with the selected field, or else with the form, or else how to pick values.
+    let invalid_count = field_errors.iter().flatten().count();
+    let status = if let Some(error) = &field_errors[app.editing_field_index]
+    {
+        Line::from(Span::styled(format!("✗ {}", error), Style::default().fg(Color::Red)))
+    }
+    else if let Some(error) = field_errors.iter().flatten().next()
+    {
+        Line::from(Span::styled(
+            format!("✗ {} invalid field(s), e.g. {}", invalid_count, error),
+            Style::default().fg(Color::Red),
+        ))
+    }
+    else if !app.field_choices(app.editing_field_index).is_empty()
+    {
+        Line::from(Span::styled(
+            "←/→ cycle values, ↑/↓ + Enter pick from the list",
+            Style::default().fg(Color::DarkGray),
+        ))
+    }
+    else
+    {
+        Line::default()
+    };
+
+    frame.render_widget(
+        Paragraph::new(status).wrap(Wrap { trim: true }),
+        layout[FORM_FIELD_COUNT],
+    );
 }
 
 fn render_process_list(frame: &mut Frame, app: &mut App, area: Rect)
@@ -1243,3 +3342,33 @@ fn render_process_list(frame: &mut Frame, app: &mut App, area: Rect)
 
     frame.render_stateful_widget(list, area, &mut app.process_list_state);
 }
+
+fn render_field_options(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let items: Vec<ListItem> = app
+        .field_options
+        .iter()
+        .map(|choice| ListItem::new(Line::from(Span::raw(choice))))
+        .collect();
+
+    let title = match app.editing_field_index
+    {
+        TYPE_FIELD => " Types ",
+        SCHED_FIELD => " Sched Policies ",
+        IOCLASS_FIELD => " IO Classes ",
+        CGROUP_FIELD => " Cgroups ",
+        _ => " Rule Files ",
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    frame.render_stateful_widget(list, area, &mut app.field_options_state);
+}
@@ -1,2 +1,3 @@
 mod process_service_tests;
+mod refresh_worker_tests;
 mod rule_service_tests;
@@ -1,4 +1,8 @@
 use ananicy_rule_o_matic::application::process_service::ProcessService;
+use ananicy_rule_o_matic::domain::models::{
+    AnanicyRule, AttributeReading, Autogroup, EnrichedRule, MatchKind, MatchingMode, OutputFormat, ProcessAttributes,
+    ProcessInfo, ProcessRecord, ProcessSnapshot, SystemCapabilities,
+};
 use std::process::{Command, Stdio};
 use std::thread;
 use std::time::Duration;
@@ -29,6 +33,7 @@ fn test_integration_process_detection()
     let found_pid = infos.iter().find(|info| info.process_id == pid);
 
     let _ = child.kill();
+    let _ = child.wait();
 
     assert!(
         found_pid.is_some(),
@@ -44,6 +49,7 @@ fn test_is_process_active_case_insensitive()
 {
     let mut child = spawn_test_process("sleep");
     let mut process_service = ProcessService::new();
+    process_service.set_matching_mode(MatchingMode::Discovery);
 
     thread::sleep(Duration::from_millis(100));
     process_service.update_processes();
@@ -62,6 +68,7 @@ fn test_is_process_active_case_insensitive()
     );
 
     let _ = child.kill();
+    let _ = child.wait();
 }
 
 #[test]
@@ -69,6 +76,7 @@ fn test_is_process_active_exe_suffix()
 {
     let mut child = spawn_test_process("sleep");
     let mut process_service = ProcessService::new();
+    process_service.set_matching_mode(MatchingMode::Discovery);
 
     thread::sleep(Duration::from_millis(100));
     process_service.update_processes();
@@ -83,6 +91,7 @@ fn test_is_process_active_exe_suffix()
     );
 
     let _ = child.kill();
+    let _ = child.wait();
 }
 
 #[test]
@@ -97,6 +106,7 @@ fn test_is_process_active_found()
     let is_active = process_service.is_process_active("sleep");
 
     let _ = child.kill();
+    let _ = child.wait();
 
     assert!(is_active, "Should return true for running process");
 }
@@ -125,9 +135,9 @@ fn test_process_info_fields()
     let infos = process_service.get_process_infos("sleep");
     let info = infos.iter().find(|i| i.process_id == pid).expect("Process not found");
 
-    assert!(info.nice.is_some(), "Nice value should be present");
+    assert!(info.nice.is_value(), "Nice value should be present");
 
-    if let Some(policy) = &info.sched_policy
+    if let AttributeReading::Value(policy) = &info.sched_policy
     {
         assert!(
             ["normal", "fifo", "rr", "batch", "idle", "deadline"].contains(&policy.as_str())
@@ -137,7 +147,7 @@ fn test_process_info_fields()
         );
     }
 
-    if let Some(ioclass) = &info.ioclass
+    if let AttributeReading::Value(ioclass) = &info.ioclass
     {
         assert!(
             ["none", "realtime", "best-effort", "idle"].contains(&ioclass.as_str()) || ioclass.starts_with("unknown"),
@@ -147,6 +157,7 @@ fn test_process_info_fields()
     }
 
     let _ = child.kill();
+    let _ = child.wait();
 }
 
 #[test]
@@ -175,6 +186,7 @@ fn test_process_detection_by_argument()
     // Spawns "sleep 5"
     let mut child = spawn_test_process("sleep");
     let mut process_service = ProcessService::new();
+    process_service.set_matching_mode(MatchingMode::Discovery);
 
     thread::sleep(Duration::from_millis(100));
     process_service.update_processes();
@@ -187,8 +199,504 @@ fn test_process_detection_by_argument()
     );
 
     let infos = process_service.get_process_infos("5");
-    assert!(!infos.is_empty(), "Should return process info when searching by argument '5'");
+    assert!(
+        !infos.is_empty(),
+        "Should return process info when searching by argument '5'"
+    );
     assert_eq!(infos[0].name, "sleep");
 
     let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn create_snapshot_record(process_id: i32, name: &str, cmdline: &[&str]) -> ProcessRecord
+{
+    create_child_snapshot_record(process_id, None, name, cmdline)
+}
+
+fn create_child_snapshot_record(
+    process_id: i32,
+    parent_process_id: Option<i32>,
+    name: &str,
+    cmdline: &[&str],
+) -> ProcessRecord
+{
+    ProcessRecord {
+        info: ProcessInfo {
+            process_id,
+            name: name.to_string(),
+            nice: AttributeReading::Value(10),
+            oom_score_adj: AttributeReading::Value(0),
+            cgroup: AttributeReading::Value("/".to_string()),
+            sched_policy: AttributeReading::Value("batch".to_string()),
+            rtprio: AttributeReading::Value(0),
+            ioclass: AttributeReading::Value("idle".to_string()),
+            latency_nice: AttributeReading::Value(0),
+            autogroup: AttributeReading::Unsupported,
+        },
+        cmdline: cmdline.iter().map(|arg| arg.to_string()).collect(),
+        exe: None,
+        uid: Some(1000),
+        parent_process_id,
+    }
+}
+
+#[test]
+fn test_snapshot_replay()
+{
+    let snapshot = ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![
+            create_snapshot_record(100, "make", &["make", "-j16"]),
+            create_snapshot_record(200, "wine64-preload", &["C:\\game\\Game.exe"]),
+        ],
+    };
+
+    let mut process_service = ProcessService::from_snapshot(snapshot);
+    process_service.update_processes();
+
+    assert_eq!(
+        process_service.snapshot_captured_at(),
+        Some("2026-01-01T12:00:00+00:00")
+    );
+
+    assert!(process_service.is_process_active("make"));
+    assert!(!process_service.is_process_active("sleep"));
+
+    let infos = process_service.get_process_infos("make");
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].process_id, 100);
+    assert_eq!(infos[0].nice, AttributeReading::Value(10));
+    assert_eq!(infos[0].sched_policy.value().map(String::as_str), Some("batch"));
+
+    assert_eq!(process_service.capture_snapshot().processes.len(), 2);
+}
+
+#[test]
+fn test_capture_live_snapshot()
+{
+    let mut child = spawn_test_process("sleep");
+    let pid = child.id() as i32;
+
+    thread::sleep(Duration::from_millis(100));
+    let process_service = ProcessService::new();
+
+    let snapshot = process_service.capture_snapshot();
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    assert!(process_service.snapshot_captured_at().is_none());
+    assert!(snapshot.capabilities.is_some());
+
+    let record = snapshot
+        .processes
+        .iter()
+        .find(|record| record.info.process_id == pid)
+        .expect("Spawned process missing from snapshot");
+
+    assert_eq!(record.info.name, "sleep");
+    assert_eq!(record.cmdline, vec!["sleep", "5"]);
+    assert!(record.uid.is_some());
+}
+
+#[test]
+fn test_exited_process_is_no_longer_active()
+{
+    let mut child = Command::new("sleep")
+        .arg("30")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to spawn process");
+    let pid = child.id() as i32;
+
+    let mut process_service = ProcessService::new();
+    process_service.set_matching_mode(MatchingMode::Discovery);
+    thread::sleep(Duration::from_millis(100));
+    process_service.update_processes();
+
+    assert!(process_service.is_process_active("30"));
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    process_service.update_processes();
+
+    assert!(!process_service.is_process_active("30"));
+    assert!(
+        !process_service
+            .get_process_infos("sleep")
+            .iter()
+            .any(|info| info.process_id == pid)
+    );
+}
+
+#[test]
+fn test_apply_scan_from_detached_scanner()
+{
+    let mut child = spawn_test_process("sleep");
+    let pid = child.id() as i32;
+
+    thread::sleep(Duration::from_millis(100));
+    let mut process_service = ProcessService::new();
+    let mut scanner = process_service
+        .take_scanner()
+        .expect("Live service should own a scanner");
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    // Without its scanner the service keeps its last known state.
+    process_service.update_processes();
+    assert!(process_service.is_process_active("sleep"));
+
+    process_service.apply_scan(scanner.scan());
+
+    assert!(
+        !process_service
+            .get_process_infos("sleep")
+            .iter()
+            .any(|info| info.process_id == pid)
+    );
+}
+
+#[test]
+fn test_applied_attributes_replace_reads_on_demand()
+{
+    let mut child = spawn_test_process("sleep");
+    let pid = child.id() as i32;
+
+    thread::sleep(Duration::from_millis(100));
+    let mut process_service = ProcessService::new();
+    process_service.take_scanner();
+
+    let mut record = create_snapshot_record(pid, "sleep", &["sleep", "5"]);
+    record.info.nice = AttributeReading::Value(19);
+
+    let mut process_attributes = ProcessAttributes::default();
+    process_attributes.infos.insert(pid, record.info);
+    process_service.apply_attributes(process_attributes);
+
+    assert_eq!(
+        process_service.get_process_info(pid).unwrap().nice,
+        AttributeReading::Value(19)
+    );
+    assert!(process_service.get_process_info(1).is_none());
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn test_exited_process_attributes_read_as_gone()
+{
+    let mut child = spawn_test_process("sleep");
+    let pid = child.id() as i32;
+
+    thread::sleep(Duration::from_millis(100));
+    let mut process_service = ProcessService::new();
+    process_service.take_scanner();
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let infos = process_service.get_process_infos("sleep");
+    let info = infos.iter().find(|i| i.process_id == pid).expect("Process not indexed");
+
+    assert_eq!(info.nice, AttributeReading::Gone);
+    assert_eq!(info.oom_score_adj, AttributeReading::Gone);
+    assert_eq!(info.cgroup, AttributeReading::Gone);
+    assert_eq!(info.sched_policy, AttributeReading::Gone);
+    assert_eq!(info.ioclass, AttributeReading::Gone);
+}
+
+#[test]
+fn test_latency_nice_is_value_or_unsupported()
+{
+    let mut child = spawn_test_process("sleep");
+    let pid = child.id() as i32;
+
+    thread::sleep(Duration::from_millis(100));
+    let process_service = ProcessService::new();
+
+    let infos = process_service.get_process_infos("sleep");
+    let info = infos.iter().find(|i| i.process_id == pid).expect("Process not found");
+
+    assert!(
+        matches!(
+            info.latency_nice,
+            AttributeReading::Value(_) | AttributeReading::Unsupported
+        ),
+        "Unexpected latency_nice reading: {:?}",
+        info.latency_nice
+    );
+    assert!(
+        matches!(
+            info.autogroup,
+            AttributeReading::Value(_) | AttributeReading::Unsupported
+        ),
+        "Unexpected autogroup reading: {:?}",
+        info.autogroup
+    );
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn test_attribute_reading_display()
+{
+    assert_eq!(AttributeReading::Value(-5).to_string(), "-5");
+    assert_eq!(AttributeReading::<i32>::Unsupported.to_string(), "unsupported");
+    assert_eq!(
+        AttributeReading::<i32>::PermissionDenied.to_string(),
+        "permission denied"
+    );
+    assert_eq!(AttributeReading::<i32>::Gone.to_string(), "process gone");
+}
+
+fn create_autogroup_service(autogroup_enabled: Option<bool>, session_members: &[&str]) -> ProcessService
+{
+    let processes = session_members
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let mut record = create_snapshot_record(300 + index as i32, name, &[name]);
+            record.info.autogroup = AttributeReading::Value(Autogroup { id: 7, nice: 0 });
+            record
+        })
+        .collect();
+
+    ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: Some(SystemCapabilities {
+            autogroup_enabled,
+            ..Default::default()
+        }),
+        processes,
+    })
+}
+
+#[test]
+fn test_autogroup_overrides_nice()
+{
+    let process_service = create_autogroup_service(Some(true), &["game", "launcher"]);
+    let info = &process_service.get_process_infos("game")[0];
+
+    assert!(process_service.autogroup_overrides_nice(Some(-5), info));
+    assert!(!process_service.autogroup_overrides_nice(Some(0), info));
+    assert!(!process_service.autogroup_overrides_nice(None, info));
+}
+
+#[test]
+fn test_autogroup_disabled_does_not_override_nice()
+{
+    let process_service = create_autogroup_service(Some(false), &["game", "launcher"]);
+    let info = &process_service.get_process_infos("game")[0];
+
+    assert!(!process_service.autogroup_overrides_nice(Some(-5), info));
+}
+
+#[test]
+fn test_process_alone_in_neutral_autogroup_keeps_its_nice()
+{
+    let process_service = create_autogroup_service(Some(true), &["game"]);
+    let info = &process_service.get_process_infos("game")[0];
+
+    assert!(!process_service.autogroup_overrides_nice(Some(-5), info));
+}
+
+#[test]
+fn test_match_explanation()
+{
+    let mut process_service = ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![
+            create_snapshot_record(10, "python3", &["/usr/bin/python3", "/opt/tools/make"]),
+            create_snapshot_record(20, "make", &["make", "-j16"]),
+            create_snapshot_record(30, "game.exe", &["C:\\game\\game.exe"]),
+            create_snapshot_record(40, "verylongprocess", &["verylongprocess"]),
+        ],
+    });
+    process_service.set_matching_mode(MatchingMode::Discovery);
+
+    assert_eq!(process_service.explain_match("make", 20), Some(MatchKind::Comm));
+    assert_eq!(
+        process_service.explain_match("make", 10),
+        Some(MatchKind::ArgvBasename { index: 1 })
+    );
+    assert_eq!(process_service.explain_match("game", 30), Some(MatchKind::ExeSuffix));
+    assert_eq!(
+        process_service.explain_match("verylongprocessname", 40),
+        Some(MatchKind::Truncated)
+    );
+    assert_eq!(process_service.explain_match("make", 30), None);
+
+    // The most reliable match comes first.
+    let infos = process_service.get_process_infos("make");
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].process_id, 20);
+    assert_eq!(infos[1].process_id, 10);
+}
+
+#[test]
+fn test_match_kind_display()
+{
+    assert_eq!(MatchKind::Comm.to_string(), "comm");
+    assert_eq!(MatchKind::ArgvBasename { index: 2 }.to_string(), "argv[2] basename");
+    assert!(MatchKind::ArgvBasename { index: 0 }.is_weak());
+    assert!(!MatchKind::Truncated.is_weak());
+}
+
+#[test]
+fn test_strict_matching_mirrors_daemon()
+{
+    let process_service = ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![
+            create_snapshot_record(10, "python3", &["/usr/bin/python3", "/opt/tools/make"]),
+            create_snapshot_record(30, "Game.exe", &["C:\\game\\Game.exe"]),
+            create_snapshot_record(40, "verylongprocess", &["verylongprocess"]),
+        ],
+    });
+
+    assert_eq!(process_service.matching_mode(), MatchingMode::Strict);
+
+    assert!(process_service.is_process_active("Game.exe"));
+    assert!(process_service.is_process_active("verylongprocessname"));
+
+    assert!(!process_service.is_process_active("make"));
+    assert!(!process_service.is_process_active("Game"));
+    assert!(!process_service.is_process_active("game.exe"));
+}
+
+#[test]
+fn test_matching_mode_from_str()
+{
+    assert_eq!("strict".parse::<MatchingMode>(), Ok(MatchingMode::Strict));
+    assert_eq!("Discovery".parse::<MatchingMode>(), Ok(MatchingMode::Discovery));
+    assert!("fuzzy".parse::<MatchingMode>().is_err());
+}
+
+#[test]
+fn test_output_format_from_str()
+{
+    assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    assert_eq!("NDJSON".parse::<OutputFormat>(), Ok(OutputFormat::Ndjson));
+    assert_eq!("csv".parse::<OutputFormat>(), Ok(OutputFormat::Csv));
+    assert!(!OutputFormat::Table.is_machine_readable());
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn test_process_tree_from_snapshot()
+{
+    let process_service = ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![
+            create_child_snapshot_record(1, Some(0), "systemd", &["/sbin/init"]),
+            create_child_snapshot_record(500, Some(1), "steam", &["steam"]),
+            create_child_snapshot_record(520, Some(510), "Game.exe", &["Game.exe"]),
+            create_child_snapshot_record(510, Some(500), "wine64-preload", &["wine64"]),
+            create_child_snapshot_record(505, Some(500), "steamwebhelper", &["steamwebhelper"]),
+            create_child_snapshot_record(900, None, "legacy", &["legacy"]),
+        ],
+    });
+
+    let tree: Vec<(i32, usize)> = process_service
+        .process_tree()
+        .iter()
+        .map(|node| (node.process_id, node.depth))
+        .collect();
+
+    assert_eq!(tree, vec![(1, 0), (500, 1), (505, 2), (510, 2), (520, 3), (900, 0)]);
+}
+
+#[test]
+fn test_match_rules_skips_shadowed_rules()
+{
+    let process_service = ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![create_snapshot_record(10, "steam", &["steam"])],
+    });
+
+    let create_rule = |nice: i32, shadowed: bool| EnrichedRule {
+        data: AnanicyRule {
+            name: Some("steam".to_string()),
+            nice: Some(nice),
+            ..Default::default()
+        },
+        context_comment: None,
+        source_file: "test.rules".into(),
+        line_number: 1,
+        shadowed,
+    };
+    let rules = vec![create_rule(5, true), create_rule(-5, false)];
+
+    let rule_matches = process_service.match_rules(&rules);
+
+    let (rule, match_kind) = rule_matches.get(&10).expect("steam should be matched");
+    assert_eq!(rule.data.nice, Some(-5));
+    assert_eq!(*match_kind, MatchKind::Comm);
+}
+
+#[test]
+fn test_rule_report_lists_mismatches()
+{
+    let process_service = ProcessService::from_snapshot(ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: None,
+        kernel_version: None,
+        capabilities: None,
+        processes: vec![
+            create_snapshot_record(10, "make", &["make"]),
+            create_snapshot_record(20, "make", &["make", "-j16"]),
+        ],
+    });
+
+    let rule = EnrichedRule {
+        data: AnanicyRule {
+            name: Some("make".to_string()),
+            nice: Some(10),
+            sched: Some("idle".to_string()),
+            ioclass: Some("IDLE".to_string()),
+            ..Default::default()
+        },
+        context_comment: Some("Build tools".to_string()),
+        source_file: "/etc/ananicy.d/00-default/make.rules".into(),
+        line_number: 1,
+        shadowed: false,
+    };
+
+    let report = process_service.rule_report(&rule);
+
+    assert_eq!(report.category, "00-default");
+    assert_eq!(report.context_comment.as_deref(), Some("Build tools"));
+    assert_eq!(report.processes.len(), 2);
+    assert_eq!(report.processes[0].info.process_id, 10);
+    assert_eq!(report.processes[0].matched_by, MatchKind::Comm);
+    assert_eq!(report.processes[0].mismatches, vec!["sched".to_string()]);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["rule"]["nice"], 10);
+    assert_eq!(json["processes"][1]["process_id"], 20);
+    assert_eq!(json["processes"][1]["matched_by"], "comm");
 }
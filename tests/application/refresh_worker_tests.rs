@@ -0,0 +1,162 @@
+use ananicy_rule_o_matic::application::refresh_worker::{RefreshWorker, WorkerEvent};
+use ananicy_rule_o_matic::application::rule_service::RuleService;
+use ananicy_rule_o_matic::infrastructure::proc_scanner::ProcScanner;
+use ananicy_rule_o_matic::infrastructure::rule_repository::RuleRepository;
+use std::collections::HashSet;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use tempfile::{TempDir, tempdir};
+
+#[test]
+fn test_worker_loads_rules_on_request()
+{
+    let rules_dir = tempdir().unwrap();
+    fs::write(
+        rules_dir.path().join("test.rules"),
+        r#"{"name": "worker_rule", "nice": 5}"#,
+    )
+    .unwrap();
+    fs::write(
+        rules_dir.path().join("test.cgroups"),
+        r#"{"cgroup": "cpu80", "CPUQuota": 80}"#,
+    )
+    .unwrap();
+    fs::write(
+        rules_dir.path().join("test.types"),
+        r#"{"type": "Game", "nice": -5, "ioclass": "best-effort"}"#,
+    )
+    .unwrap();
+
+    let rule_service = RuleService::new(RuleRepository::new_with_base_path(rules_dir.path().to_path_buf()));
+
+    thread::scope(|scope| {
+        let worker = RefreshWorker::spawn(scope, &rule_service, None, None, Duration::from_secs(60));
+        worker.reload_rules();
+
+        let worker_event = worker.recv_timeout(Duration::from_secs(5));
+
+        match worker_event
+        {
+            Some(WorkerEvent::RulesLoaded(Ok((rules, errors)))) =>
+            {
+                assert_eq!(rules.len(), 1);
+                assert_eq!(rules[0].data.name.as_deref(), Some("worker_rule"));
+                assert!(errors.is_empty());
+            }
+            _ => panic!("Expected rules to be loaded"),
+        }
+
+        match worker.recv_timeout(Duration::from_secs(5))
+        {
+            Some(WorkerEvent::CgroupsLoaded(Ok((definitions, errors)))) =>
+            {
+                assert_eq!(definitions.len(), 1);
+                assert_eq!(definitions[0].cpu_quota, Some(80));
+                assert!(errors.is_empty());
+            }
+            _ => panic!("Expected cgroup definitions to be loaded"),
+        }
+
+        match worker.recv_timeout(Duration::from_secs(5))
+        {
+            Some(WorkerEvent::TypesLoaded(Ok((definitions, errors)))) =>
+            {
+                assert_eq!(definitions.len(), 1);
+                assert_eq!(definitions[0].name.as_deref(), Some("Game"));
+                assert_eq!(definitions[0].nice, Some(-5));
+                assert!(errors.is_empty());
+            }
+            _ => panic!("Expected type definitions to be loaded"),
+        }
+
+        worker.shutdown();
+    });
+}
+
+fn create_fake_process(proc_dir: &TempDir, process_id: i32, comm: &str)
+{
+    let process_dir = proc_dir.path().join(process_id.to_string());
+    fs::create_dir_all(&process_dir).unwrap();
+    fs::write(process_dir.join("comm"), format!("{}\n", comm)).unwrap();
+    fs::write(process_dir.join("cmdline"), format!("{}\0", comm)).unwrap();
+}
+
+// The process ids of the next attribute readings the worker sends.
+fn next_attributes(worker: &RefreshWorker) -> Vec<i32>
+{
+    while let Some(worker_event) = worker.recv_timeout(Duration::from_secs(5))
+    {
+        if let WorkerEvent::AttributesRead(process_attributes) = worker_event
+        {
+            let mut process_ids: Vec<i32> = process_attributes.infos.into_keys().collect();
+            process_ids.sort_unstable();
+            return process_ids;
+        }
+    }
+
+    panic!("Worker did not read any attributes");
+}
+
+#[test]
+fn test_worker_scans_processes_periodically()
+{
+    let rules_dir = tempdir().unwrap();
+    let proc_dir = tempdir().unwrap();
+
+    create_fake_process(&proc_dir, 77, "gamescope");
+
+    let rule_service = RuleService::new(RuleRepository::new_with_base_path(rules_dir.path().to_path_buf()));
+    let scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+
+    thread::scope(|scope| {
+        let worker = RefreshWorker::spawn(scope, &rule_service, Some(scanner), None, Duration::from_millis(10));
+
+        let mut scan_delta = None;
+
+        while let Some(worker_event) = worker.recv_timeout(Duration::from_secs(5))
+        {
+            match worker_event
+            {
+                WorkerEvent::ProcessesScanned(delta) =>
+                {
+                    scan_delta = Some(delta);
+                    break;
+                }
+                WorkerEvent::AttributesRead(_) => panic!("Attributes read for an unwatched process"),
+                _ =>
+                {}
+            }
+        }
+
+        let scan_delta = scan_delta.expect("Worker did not report a process scan");
+
+        assert_eq!(scan_delta.added.len(), 1);
+        assert_eq!(scan_delta.added[0].0, 77);
+        assert_eq!(scan_delta.added[0].1.name, "gamescope");
+
+        worker.watch_processes(HashSet::from([77, 78]));
+        assert_eq!(next_attributes(&worker), vec![77]);
+
+        // Only the process that showed up is read, the earlier reading of 77 is kept.
+        create_fake_process(&proc_dir, 78, "wine");
+        assert_eq!(next_attributes(&worker), vec![78]);
+
+        worker.shutdown();
+    });
+}
+
+#[test]
+fn test_worker_without_scanner_only_loads_rules()
+{
+    let rules_dir = tempdir().unwrap();
+    let rule_service = RuleService::new(RuleRepository::new_with_base_path(rules_dir.path().to_path_buf()));
+
+    thread::scope(|scope| {
+        let worker = RefreshWorker::spawn(scope, &rule_service, None, None, Duration::from_millis(10));
+
+        assert!(worker.recv_timeout(Duration::from_millis(100)).is_none());
+
+        worker.shutdown();
+    });
+}
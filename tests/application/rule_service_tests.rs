@@ -1,5 +1,11 @@
 use ananicy_rule_o_matic::application::rule_service::RuleService;
+use ananicy_rule_o_matic::domain::fuzzy::fuzzy_match;
+use ananicy_rule_o_matic::domain::models::{
+    AnanicyRule, BulkAction, EnrichedRule, RuleColumn, RuleSort, RuleTreeNodeKind,
+};
+use ananicy_rule_o_matic::domain::query::{Comparison, NumberField, Query, SearchMode, Term};
 use ananicy_rule_o_matic::infrastructure::rule_repository::RuleRepository;
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -34,7 +40,7 @@ fn test_search_rules_basic()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, _) = rule_service.search_rules("").unwrap();
+    let (rules, _) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules.len(), 1);
     assert_eq!(rules[0].data.name.as_deref(), Some("test-process"));
@@ -58,7 +64,7 @@ fn test_shadowing_logic()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, _) = rule_service.search_rules("").unwrap();
+    let (rules, _) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules.len(), 2);
 
@@ -91,19 +97,23 @@ fn test_filtering()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (result, _) = rule_service.search_rules("foo").unwrap();
+    let (result, _) = rule_service.search_rules("foo", SearchMode::Exact, |_| false).unwrap();
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].data.name.as_deref(), Some("foo"));
 
-    let (result, _) = rule_service.search_rules("bg").unwrap();
+    let (result, _) = rule_service.search_rules("bg", SearchMode::Exact, |_| false).unwrap();
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].data.name.as_deref(), Some("bar"));
 
-    let (result, _) = rule_service.search_rules("system").unwrap();
+    let (result, _) = rule_service
+        .search_rules("system", SearchMode::Exact, |_| false)
+        .unwrap();
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].data.name.as_deref(), Some("baz"));
 
-    let (result, _) = rule_service.search_rules("nonexistent").unwrap();
+    let (result, _) = rule_service
+        .search_rules("nonexistent", SearchMode::Exact, |_| false)
+        .unwrap();
     assert_eq!(result.len(), 0);
 }
 
@@ -118,7 +128,7 @@ fn test_sorting_by_category_and_name()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, _) = rule_service.search_rules("").unwrap();
+    let (rules, _) = rule_service.load_rules().unwrap();
     assert_eq!(rules.len(), 2);
 
     assert_eq!(rules[0].data.name.as_deref(), Some("z_rule"));
@@ -141,7 +151,7 @@ fn test_sorting_same_category_by_name()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, _) = rule_service.search_rules("").unwrap();
+    let (rules, _) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules[0].data.name.as_deref(), Some("a_rule"));
     assert_eq!(rules[1].data.name.as_deref(), Some("b_rule"));
@@ -163,7 +173,7 @@ fn test_context_comments()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, _) = rule_service.search_rules("").unwrap();
+    let (rules, _) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules[0].context_comment.as_deref(), Some("# This is a comment"));
 }
@@ -185,15 +195,15 @@ fn test_invalid_json_is_skipped_and_reported()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, errors) = rule_service.search_rules("").unwrap();
+    let (rules, errors) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules.len(), 2);
     assert_eq!(rules[0].data.name.as_deref(), Some("also_valid"));
     assert_eq!(rules[1].data.name.as_deref(), Some("valid"));
 
     assert_eq!(errors.len(), 1);
-    assert!(errors[0].contains("Parse error"));
-    assert!(errors[0].contains("line 3"));
+    assert!(errors[0].message.contains("Parse error"));
+    assert!(errors[0].message.contains("line 3"));
 }
 
 #[test]
@@ -205,8 +215,777 @@ fn test_io_error_reporting()
     let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
     let rule_service = RuleService::new(rule_repository);
 
-    let (rules, errors) = rule_service.search_rules("").unwrap();
+    let (rules, errors) = rule_service.load_rules().unwrap();
 
     assert_eq!(rules.len(), 0);
     assert_eq!(errors.len(), 1);
 }
+
+#[test]
+fn test_later_cgroup_definition_wins()
+{
+    let temp_dir = TempDir::new().unwrap();
+    create_rule_file(
+        &temp_dir,
+        "00-cgroups/base.cgroups",
+        r#"{"cgroup": "cpu80", "CPUQuota": 80}"#,
+    );
+    create_rule_file(
+        &temp_dir,
+        "99-custom/override.cgroups",
+        r#"{"cgroup": "cpu80", "CPUQuota": 60}"#,
+    );
+
+    let rule_service = RuleService::new(RuleRepository::new_with_base_path(temp_dir.path().to_path_buf()));
+    let (definitions, errors) = rule_service.load_cgroup_definitions().unwrap();
+
+    assert!(errors.is_empty());
+
+    let definition = RuleService::find_cgroup_definition(&definitions, "cpu80").unwrap();
+    assert_eq!(definition.cpu_quota, Some(60));
+    assert!(RuleService::find_cgroup_definition(&definitions, "cpu50").is_none());
+}
+
+fn create_rule_service(dir: &TempDir) -> RuleService
+{
+    RuleService::new(RuleRepository::new_with_base_path(dir.path().to_path_buf()))
+}
+
+#[test]
+fn test_add_rule_rejects_duplicates_and_invalid_values()
+{
+    let temp_dir = TempDir::new().unwrap();
+    create_rule_file(&temp_dir, "00-default/games.rules", r#"{"name": "game", "nice": -5}"#);
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let rule = AnanicyRule {
+        name: Some("editor".to_string()),
+        nice: Some(5),
+        ..Default::default()
+    };
+    let file_path = rule_service.add_rule(&rule, None).unwrap();
+
+    assert_eq!(file_path, temp_dir.path().join("99-custom/editor.rules"));
+    assert_eq!(rule_service.find_rule("editor").unwrap().data.nice, Some(5));
+
+    let duplicate = AnanicyRule {
+        name: Some("game".to_string()),
+        ..Default::default()
+    };
+    assert!(rule_service.add_rule(&duplicate, None).is_err());
+
+    let invalid = AnanicyRule {
+        name: Some("broken".to_string()),
+        nice: Some(50),
+        ..Default::default()
+    };
+    assert!(rule_service.add_rule(&invalid, None).is_err());
+    assert!(!temp_dir.path().join("99-custom/broken.rules").exists());
+}
+
+#[test]
+fn test_rule_problems_name_their_field()
+{
+    let rule = AnanicyRule {
+        nice: Some(25),
+        sched: Some("fast".to_string()),
+        ioclass: Some("idle".to_string()),
+        ..Default::default()
+    };
+
+    let fields: Vec<&str> = rule.problems().into_iter().map(|(field, _)| field).collect();
+    assert_eq!(fields, vec!["name", "nice", "sched"]);
+
+    let error = rule.validate().unwrap_err().to_string();
+    assert!(error.contains("nice must be between -20 and 19, got 25"));
+}
+
+#[test]
+fn test_edit_rule_keeps_other_lines()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "# Games\n{\"name\": \"game\", \"nice\": -5}\n{\"name\": \"other\", \"nice\": 1}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let changes = vec![
+        ("nice".to_string(), "5".to_string()),
+        ("ioclass".to_string(), "idle".to_string()),
+    ];
+    let rule = rule_service.edit_rule("game", &changes).unwrap();
+
+    assert_eq!(rule.data.nice, Some(5));
+
+    let content = fs::read_to_string(&file_path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "# Games");
+    assert_eq!(lines[2], r#"{"name": "other", "nice": 1}"#);
+    assert_eq!(
+        rule_service.find_rule("game").unwrap().data.ioclass.as_deref(),
+        Some("idle")
+    );
+
+    let unknown = vec![("colour".to_string(), "red".to_string())];
+    assert!(rule_service.edit_rule("game", &unknown).is_err());
+
+    let out_of_range = vec![("nice".to_string(), "-30".to_string())];
+    assert!(rule_service.edit_rule("game", &out_of_range).is_err());
+    assert_eq!(rule_service.find_rule("game").unwrap().data.nice, Some(5));
+}
+
+#[test]
+fn test_edit_rule_touches_the_rule_in_effect_and_rejects_colliding_renames()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"game\", \"nice\": 1}\n{\"name\": \"game\", \"nice\": 2}\n{\"name\": \"other\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    rule_service
+        .edit_rule("game", &[("nice".to_string(), "7".to_string())])
+        .unwrap();
+
+    let content = fs::read_to_string(&file_path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], r#"{"name": "game", "nice": 1}"#);
+    assert_eq!(lines[1], r#"{"name":"game","nice":7}"#);
+
+    let rename = vec![("name".to_string(), "other".to_string())];
+    let error = rule_service.edit_rule("game", &rename).unwrap_err();
+    assert!(error.to_string().contains("already exists"));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), content);
+
+    assert_eq!(rule_service.remove_rule("game").unwrap(), file_path);
+    assert_eq!(rule_service.find_rule("game").unwrap().data.nice, Some(1));
+}
+
+#[test]
+fn test_remove_rule()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"game\"}\n{\"name\": \"other\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    assert_eq!(rule_service.remove_rule("game").unwrap(), file_path);
+    assert!(rule_service.find_rule("game").is_err());
+    assert!(rule_service.find_rule("other").is_ok());
+    assert!(rule_service.remove_rule("game").is_err());
+}
+
+#[test]
+fn test_edits_keep_crlf_line_endings_and_missing_final_newline()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "# Games\r\n{\"name\": \"game\", \"nice\": -5}\r\n{\"name\": \"gone\"}\r\n{\"name\": \"other\"}",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    rule_service
+        .edit_rule("game", &[("nice".to_string(), "5".to_string())])
+        .unwrap();
+    rule_service.remove_rule("gone").unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "# Games\r\n{\"name\":\"game\",\"nice\":5}\r\n{\"name\": \"other\"}"
+    );
+
+    rule_service
+        .edit_rule("other", &[("nice".to_string(), "1".to_string())])
+        .unwrap();
+
+    assert!(
+        fs::read_to_string(&file_path)
+            .unwrap()
+            .ends_with("}\r\n{\"name\":\"other\",\"nice\":1}")
+    );
+}
+
+#[test]
+fn test_move_rule()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let source_file = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"game\", \"nice\": -5}\n{\"name\": \"other\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    assert!(
+        rule_service
+            .move_rule("game", &PathBuf::from("50-games/game.txt"))
+            .is_err()
+    );
+
+    let target_file = rule_service
+        .move_rule("game", &PathBuf::from("50-games/game.rules"))
+        .unwrap();
+
+    assert_eq!(target_file, temp_dir.path().join("50-games/game.rules"));
+
+    let rule = rule_service.find_rule("game").unwrap();
+    assert_eq!(rule.source_file, target_file);
+    assert_eq!(rule.data.nice, Some(-5));
+    assert!(!fs::read_to_string(&source_file).unwrap().contains("\"game\""));
+}
+
+#[test]
+fn test_add_rule_to_target_file()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(&temp_dir, "00-default/games.rules", "{\"name\": \"game\"}\n");
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let mut rule = AnanicyRule {
+        name: Some("other-game".to_string()),
+        ..Default::default()
+    };
+
+    assert!(
+        rule_service
+            .add_rule(&rule, Some(&PathBuf::from("00-default/games.txt")))
+            .is_err()
+    );
+
+    let file_path = rule_service
+        .add_rule(&rule, Some(&PathBuf::from("00-default/games.rules")))
+        .unwrap();
+
+    assert_eq!(file_path, games_file);
+    assert_eq!(
+        fs::read_to_string(&games_file).unwrap(),
+        "{\"name\": \"game\"}\n{\"name\":\"other-game\"}\n"
+    );
+
+    rule.name = Some("new-game".to_string());
+
+    let file_path = rule_service
+        .add_rule(&rule, Some(&PathBuf::from("50-games/games.rules")))
+        .unwrap();
+
+    assert_eq!(file_path, temp_dir.path().join("50-games/games.rules"));
+    assert_eq!(rule_service.find_rule("new-game").unwrap().category(), "50-games");
+}
+
+#[test]
+fn test_save_rule_updates_target_file_in_place()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"game\", \"nice\": -5}\n{\"name\": \"other\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let mut rule = rule_service.find_rule("game").unwrap().data;
+    rule.nice = Some(-10);
+
+    rule_service.save_rule(&rule, Some(&games_file)).unwrap();
+
+    let (rules, _) = rule_service.load_rules().unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rule_service.find_rule("game").unwrap().data.nice, Some(-10));
+    assert_eq!(RuleService::rule_files(&rules), vec![games_file]);
+}
+
+#[test]
+fn test_plan_edit_rule_renames_in_place()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"foo\", \"nice\": 5}\n{\"name\": \"other\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+    let original = rule_service.find_rule("foo").unwrap();
+
+    let mut rule = original.data.clone();
+    rule.name = Some("bar".to_string());
+
+    let save_plan = rule_service.plan_edit_rule(&original, &rule, None).unwrap();
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    let content = fs::read_to_string(&games_file).unwrap();
+    assert!(!content.contains("foo"));
+    assert_eq!(content.lines().count(), 2);
+    assert!(rule_service.find_rule("foo").is_err());
+
+    let renamed = rule_service.find_rule("bar").unwrap();
+    assert_eq!(renamed.line_number, 1);
+    assert_eq!(renamed.data.nice, Some(5));
+
+    rule.name = Some("other".to_string());
+    let error = rule_service.plan_edit_rule(&renamed, &rule, None).unwrap_err();
+    assert!(error.to_string().contains("already defines"));
+
+    let moved_file = temp_dir.path().join("50-games/moved.rules");
+    let save_plan = rule_service
+        .plan_edit_rule(&renamed, &renamed.data, Some(&moved_file))
+        .unwrap();
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    assert_eq!(rule_service.find_rule("bar").unwrap().source_file, moved_file);
+    assert!(!fs::read_to_string(&games_file).unwrap().contains("bar"));
+}
+
+#[test]
+fn test_plan_save_rule_reports_newly_shadowed_rules()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(&temp_dir, "00-default/games.rules", "{\"name\": \"game\"}\n");
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let rule = AnanicyRule {
+        name: Some("game".to_string()),
+        nice: Some(-10),
+        ..Default::default()
+    };
+
+    let save_plan = rule_service
+        .plan_save_rule(&rule, Some(&PathBuf::from("50-games/games.rules")))
+        .unwrap();
+
+    assert_eq!(save_plan.changes.len(), 1);
+    assert_eq!(save_plan.changes[0].file, temp_dir.path().join("50-games/games.rules"));
+    assert_eq!(save_plan.changes[0].old_content, "");
+    assert_eq!(save_plan.shadowed_rules.len(), 1);
+    assert_eq!(save_plan.shadowed_rules[0].source_file, games_file);
+    assert!(!save_plan.changes[0].file.exists());
+
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    assert_eq!(rule_service.find_rule("game").unwrap().data.nice, Some(-10));
+}
+
+#[test]
+fn test_plan_bulk_action_sets_field_disables_and_moves()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "# Games\n{\"name\": \"one\"}\n{\"name\": \"two\"}\n{\"name\": \"keep\"}\n",
+    );
+    create_rule_file(&temp_dir, "00-default/tools.rules", "{\"name\": \"three\"}\n");
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    let selected_rules = |names: &[&str]| -> Vec<EnrichedRule> {
+        let (rules, _) = rule_service.load_rules().unwrap();
+
+        rules
+            .into_iter()
+            .filter(|rule| names.contains(&rule.data.name.as_deref().unwrap()))
+            .collect()
+    };
+
+    let set_type = BulkAction::SetField {
+        field: "type".to_string(),
+        value: "Game".to_string(),
+    };
+    let save_plan = rule_service
+        .plan_bulk_action(&selected_rules(&["one", "two", "three"]), &set_type)
+        .unwrap();
+    assert_eq!(save_plan.changes.len(), 2);
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    for name in ["one", "two", "three"]
+    {
+        assert_eq!(
+            rule_service.find_rule(name).unwrap().data.rule_type.as_deref(),
+            Some("Game")
+        );
+    }
+    assert_eq!(rule_service.find_rule("keep").unwrap().data.rule_type, None);
+
+    let save_plan = rule_service
+        .plan_bulk_action(&selected_rules(&["one"]), &BulkAction::Disable)
+        .unwrap();
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    assert!(rule_service.find_rule("one").is_err());
+    assert!(
+        fs::read_to_string(&games_file)
+            .unwrap()
+            .contains("#disabled# {\"name\":\"one\"")
+    );
+    // The disabled line is not taken for a context comment.
+    assert_eq!(
+        rule_service.find_rule("keep").unwrap().context_comment.as_deref(),
+        Some("# Games")
+    );
+
+    let save_plan = rule_service
+        .plan_bulk_action(
+            &selected_rules(&["two", "three"]),
+            &BulkAction::MoveTo(PathBuf::from("50-games/moved.rules")),
+        )
+        .unwrap();
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    let moved_file = temp_dir.path().join("50-games/moved.rules");
+    assert_eq!(rule_service.find_rule("two").unwrap().source_file, moved_file);
+    assert_eq!(rule_service.find_rule("three").unwrap().source_file, moved_file);
+    assert_eq!(rule_service.find_rule("keep").unwrap().source_file, games_file);
+    assert_eq!(
+        rule_service.find_rule("two").unwrap().context_comment.as_deref(),
+        Some("# Games")
+    );
+    assert_eq!(rule_service.find_rule("three").unwrap().context_comment, None);
+}
+
+#[test]
+fn test_marks_follow_rules_when_lines_shift()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        "{\"name\": \"one\"}\n{\"name\": \"two\"}\n{\"name\": \"three\"}\n",
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+    let marks = HashSet::from([RuleService::mark_key(&rule_service.find_rule("two").unwrap())]);
+
+    // Edited elsewhere, a rule above the marked one moves it down a line.
+    fs::write(
+        &games_file,
+        "{\"name\": \"zero\"}\n{\"name\": \"one\"}\n{\"name\": \"two\"}\n{\"name\": \"three\"}\n",
+    )
+    .unwrap();
+
+    let (rules, _) = rule_service.load_rules().unwrap();
+    let marked_rules = RuleService::marked_rules(&rules, &marks);
+    assert_eq!(marked_rules.len(), 1);
+    assert_eq!(marked_rules[0].line_number, 3);
+
+    let save_plan = rule_service
+        .plan_bulk_action(&marked_rules, &BulkAction::Delete)
+        .unwrap();
+    rule_service.apply_save_plan(&save_plan).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&games_file).unwrap(),
+        "{\"name\": \"zero\"}\n{\"name\": \"one\"}\n{\"name\": \"three\"}\n"
+    );
+}
+
+#[test]
+fn test_plan_bulk_action_rejects_invalid_values_and_stale_lines()
+{
+    let temp_dir = TempDir::new().unwrap();
+    let games_file = create_rule_file(&temp_dir, "00-default/games.rules", "{\"name\": \"one\"}\n");
+
+    let rule_service = create_rule_service(&temp_dir);
+    let (rules, _) = rule_service.load_rules().unwrap();
+
+    let set_nice = BulkAction::SetField {
+        field: "nice".to_string(),
+        value: "100".to_string(),
+    };
+    assert!(rule_service.plan_bulk_action(&rules, &set_nice).is_err());
+
+    fs::write(&games_file, "{\"name\": \"other\"}\n{\"name\": \"one\"}\n").unwrap();
+
+    let error = rule_service.plan_bulk_action(&rules, &BulkAction::Delete).unwrap_err();
+    assert!(error.to_string().contains("no longer holds rule"));
+}
+
+fn search_names(rule_service: &RuleService, query: &str) -> Vec<String>
+{
+    let (rules, _) = rule_service
+        .search_rules(query, SearchMode::Exact, |rule_name| rule_name == "steam")
+        .unwrap();
+
+    rules.into_iter().filter_map(|rule| rule.data.name).collect()
+}
+
+#[test]
+fn test_structured_query()
+{
+    let temp_dir = TempDir::new().unwrap();
+    create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        r#"
+        {"name": "steam", "type": "Game", "nice": -1}
+        {"name": "wine", "type": "Game", "nice": 10, "sched": "idle"}
+    "#,
+    );
+    create_rule_file(
+        &temp_dir,
+        "10-tools/tools.rules",
+        r#"
+        # Build tools
+        {"name": "make", "type": "BG_CPUIO", "nice": 19, "ioclass": "idle"}
+        {"name": "steam", "type": "Game", "nice": -5}
+    "#,
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+
+    assert!(search_names(&rule_service, "1").is_empty());
+    assert_eq!(search_names(&rule_service, "10"), vec!["wine"]);
+    assert_eq!(search_names(&rule_service, "type:game nice:<0"), vec!["steam", "steam"]);
+    assert_eq!(
+        search_names(&rule_service, "type:game nice:<0 shadowed:no"),
+        vec!["steam"]
+    );
+    assert_eq!(
+        search_names(&rule_service, "sched:idle OR ioclass:idle"),
+        vec!["wine", "make"]
+    );
+    assert_eq!(search_names(&rule_service, "file:tools NOT active:yes"), vec!["make"]);
+    assert_eq!(
+        search_names(&rule_service, "type:game AND (nice:>=10 OR \"build tools\")"),
+        vec!["wine", "steam"]
+    );
+    assert_eq!(
+        search_names(&rule_service, "comment:\"build tools\" name:=make"),
+        vec!["make"]
+    );
+    assert!(search_names(&rule_service, "name:=mak").is_empty());
+}
+
+#[test]
+fn test_query_parse()
+{
+    assert_eq!(Query::parse("  ").unwrap(), Query::All);
+    assert_eq!(
+        Query::parse("nice:>=5").unwrap(),
+        Query::Term(Term::Number {
+            field: NumberField::Nice,
+            comparison: Comparison::GreaterOrEqual,
+            value: 5,
+        })
+    );
+    assert!(Query::parse("active:yes").unwrap().depends_on_processes());
+    assert!(!Query::parse("NOT shadowed:yes").unwrap().depends_on_processes());
+
+    assert!(Query::parse("colour:red").is_err());
+    assert!(Query::parse("nice:abc").is_err());
+    assert!(Query::parse("nice:").is_err());
+    assert!(Query::parse("(type:game").is_err());
+    assert!(Query::parse("type:game)").is_err());
+    assert!(Query::parse("\"unterminated").is_err());
+    assert!(Query::parse("OR steam").is_err());
+    assert!(Query::parse("steam AND").is_err());
+}
+
+#[test]
+fn test_fuzzy_search_ranks_names_above_comments()
+{
+    let temp_dir = TempDir::new().unwrap();
+    create_rule_file(
+        &temp_dir,
+        "00-default/web.rules",
+        r#"
+        # Runs next to steamwebhelper
+        {"name": "webhelper-daemon"}
+    "#,
+    );
+    create_rule_file(
+        &temp_dir,
+        "10-games/steam.rules",
+        r#"
+        {"name": "steamwebhelper", "type": "Game"}
+        {"name": "steam", "type": "Game"}
+    "#,
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+    let fuzzy_names = |query: &str| -> Vec<String> {
+        let (rules, _) = rule_service.search_rules(query, SearchMode::Fuzzy, |_| false).unwrap();
+        rules.into_iter().filter_map(|rule| rule.data.name).collect()
+    };
+
+    assert_eq!(fuzzy_names("stmwbh"), vec!["steamwebhelper"]);
+    assert_eq!(
+        fuzzy_names("steamwebhelper"),
+        vec!["steamwebhelper", "webhelper-daemon"]
+    );
+    assert_eq!(fuzzy_names("steem"), vec!["steam", "steamwebhelper"]);
+    assert_eq!(fuzzy_names("steam")[0], "steam");
+    assert!(fuzzy_names("stmwbh type:bg").is_empty());
+
+    let (exact_rules, _) = rule_service
+        .search_rules("stmwbh", SearchMode::Exact, |_| false)
+        .unwrap();
+    assert!(exact_rules.is_empty());
+}
+
+#[test]
+fn test_fuzzy_match_positions()
+{
+    let subsequence = fuzzy_match("stmwbh", "steamwebhelper").unwrap();
+    assert_eq!(subsequence.positions, vec![0, 1, 4, 5, 7, 8]);
+
+    let substring = fuzzy_match("web", "SteamWebHelper").unwrap();
+    assert_eq!(substring.positions, vec![5, 6, 7]);
+    assert!(substring.score > subsequence.score);
+
+    let typo = fuzzy_match("stema", "steam").unwrap();
+    assert!(typo.positions.is_empty());
+    assert!(typo.score < subsequence.score);
+
+    assert!(fuzzy_match("xyz", "steam").is_none());
+
+    // 'İ' lowercases to two chars, positions still point into the original name.
+    let dotted = fuzzy_match("game", "İstanbulGame").unwrap();
+    assert_eq!(dotted.positions, vec![8, 9, 10, 11]);
+    assert_eq!(
+        Query::parse("stmwbh")
+            .unwrap()
+            .highlight("steamwebhelper", SearchMode::Fuzzy),
+        vec![0, 1, 4, 5, 7, 8]
+    );
+}
+
+#[test]
+fn test_compute_statistics()
+{
+    let temp_dir = TempDir::new().unwrap();
+    create_rule_file(
+        &temp_dir,
+        "00-default/games.rules",
+        r#"
+        {"name": "steam", "type": "Game", "nice": -5}
+        {"name": "wine", "type": "Game", "nice": -5, "latency_nice": -10}
+        {broken
+    "#,
+    );
+    create_rule_file(
+        &temp_dir,
+        "10-tools/tools.rules",
+        r#"
+        {"name": "make", "nice": 19, "oom_score_adj": 500}
+        {"name": "steam", "type": "Game", "nice": -1}
+    "#,
+    );
+
+    let rule_service = create_rule_service(&temp_dir);
+    let (rules, errors) = rule_service.load_rules().unwrap();
+
+    let statistics = RuleService::compute_statistics(&rules, &errors, |rule_name| rule_name == "steam");
+
+    assert_eq!(statistics.total_rules, 4);
+    assert_eq!(statistics.parse_errors, 1);
+    assert_eq!(statistics.shadowed_rules, 1);
+    assert_eq!(statistics.active_rules, 1);
+    assert_eq!(statistics.rules_per_category.get("00-default"), Some(&2));
+    assert_eq!(statistics.rules_per_category.get("10-tools"), Some(&2));
+    assert_eq!(
+        statistics
+            .rules_per_file
+            .get(&temp_dir.path().join("10-tools/tools.rules")),
+        Some(&2)
+    );
+    assert_eq!(statistics.rules_per_type.get("Game"), Some(&3));
+    assert_eq!(statistics.rules_per_type.get("-"), Some(&1));
+    assert_eq!(statistics.nice_histogram.get(&-5), Some(&2));
+    assert_eq!(statistics.latency_nice_histogram.len(), 1);
+    assert_eq!(statistics.oom_score_adj_histogram.get(&500), Some(&1));
+}
+
+#[test]
+fn test_rule_tree_groups_rules_by_folder_and_file()
+{
+    let temp_dir = TempDir::new().unwrap();
+
+    create_rule_file(
+        &temp_dir,
+        "00-default/a.rules",
+        "{\"name\": \"one\"}\n{\"name\": \"two\"}\n",
+    );
+    let nested_file = create_rule_file(&temp_dir, "00-default/games/b.rules", r#"{"name": "three"}"#);
+
+    let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
+    let rule_service = RuleService::new(rule_repository);
+
+    let (rules, _) = rule_service.load_rules().unwrap();
+    let tree = RuleService::rule_tree(rule_service.rule_roots(), &rules);
+
+    let summary: Vec<_> = tree
+        .iter()
+        .map(|node| (node.kind, node.depth, node.label(), node.rule_count))
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            (RuleTreeNodeKind::Root, 0, temp_dir.path().display().to_string(), 3),
+            (RuleTreeNodeKind::Directory, 1, "00-default".to_string(), 3),
+            (RuleTreeNodeKind::File, 2, "a.rules".to_string(), 2),
+            (RuleTreeNodeKind::Directory, 2, "games".to_string(), 1),
+            (RuleTreeNodeKind::File, 3, "b.rules".to_string(), 1),
+        ]
+    );
+
+    let games = &tree[3];
+    let filtered: Vec<_> = rules.iter().filter(|rule| games.contains(rule)).collect();
+
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].source_file, nested_file);
+}
+
+#[test]
+fn test_sort_rules_by_column_keeps_missing_values_last()
+{
+    let temp_dir = TempDir::new().unwrap();
+
+    create_rule_file(
+        &temp_dir,
+        "00-default/a.rules",
+        "{\"name\": \"alpha\", \"nice\": 5}\n{\"name\": \"beta\"}\n{\"name\": \"gamma\", \"nice\": -10}\n",
+    );
+
+    let rule_repository = RuleRepository::new_with_base_path(temp_dir.path().to_path_buf());
+    let rule_service = RuleService::new(rule_repository);
+
+    let (mut rules, _) = rule_service.load_rules().unwrap();
+    let names =
+        |rules: &[EnrichedRule]| -> Vec<String> { rules.iter().map(|rule| rule.data.name.clone().unwrap()).collect() };
+
+    let mut sort = RuleSort {
+        column: RuleColumn::Nice,
+        descending: false,
+    };
+    RuleService::sort_rules_by(&mut rules, sort, |_| false);
+    assert_eq!(names(&rules), ["gamma", "alpha", "beta"]);
+
+    sort.descending = true;
+    RuleService::sort_rules_by(&mut rules, sort, |_| false);
+    assert_eq!(names(&rules), ["alpha", "gamma", "beta"]);
+
+    sort.column = RuleColumn::Active;
+    RuleService::sort_rules_by(&mut rules, sort, |name| name == "beta");
+    assert_eq!(names(&rules), ["beta", "alpha", "gamma"]);
+}
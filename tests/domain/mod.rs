@@ -0,0 +1 @@
+mod pagination_tests;
@@ -0,0 +1,82 @@
+use ananicy_rule_o_matic::domain::pagination::Pagination;
+
+#[test]
+fn test_rows_for_height_leaves_room_for_the_header()
+{
+    assert_eq!(Pagination::rows_for_height(40, 50), 37);
+    assert_eq!(Pagination::rows_for_height(40, 20), 20);
+
+    // Too short for borders and header, a page still holds one rule.
+    assert_eq!(Pagination::rows_for_height(3, 50), 1);
+    assert_eq!(Pagination::rows_for_height(2, 50), 1);
+    assert_eq!(Pagination::rows_for_height(0, 50), 1);
+}
+
+#[test]
+fn test_select_turns_to_the_page_of_the_index()
+{
+    let mut pagination = Pagination::new(10);
+
+    assert_eq!(pagination.select(25, 100), Some(5));
+    assert_eq!(pagination.current_page, 2);
+    assert_eq!(pagination.index_of(5), 25);
+    assert_eq!(pagination.page_range(100), 20..30);
+
+    // Past the end the last rule is selected.
+    assert_eq!(pagination.select(500, 42), Some(1));
+    assert_eq!(pagination.current_page, 4);
+    assert_eq!(pagination.page_range(42), 40..42);
+
+    assert_eq!(pagination.select(3, 0), None);
+    assert_eq!(pagination.current_page, 0);
+    assert_eq!(pagination.page_range(0), 0..0);
+    assert_eq!(pagination.page_count(0), 1);
+}
+
+#[test]
+fn test_resize_keeps_the_selected_rule()
+{
+    let mut pagination = Pagination::new(10);
+    pagination.select(25, 100);
+
+    // With six rules per page, rule 25 is row 1 of page 4.
+    assert_eq!(pagination.resize(6, Some(5), 100), Some(1));
+    assert_eq!(pagination.current_page, 4);
+    assert_eq!(pagination.index_of(1), 25);
+
+    assert_eq!(pagination.resize(6, Some(1), 100), Some(1));
+    assert_eq!(pagination.current_page, 4);
+
+    // A terminal shorter than the header shrinks pages to one rule.
+    let rows = Pagination::rows_for_height(2, 50);
+    assert_eq!(pagination.resize(rows, Some(1), 100), Some(0));
+    assert_eq!(pagination.current_page, 25);
+    assert_eq!(pagination.page_count(100), 100);
+}
+
+#[test]
+fn test_resize_without_selection_keeps_the_first_visible_rule()
+{
+    let mut pagination = Pagination::new(10);
+    pagination.current_page = 3;
+
+    assert_eq!(pagination.resize(4, None, 100), None);
+    assert_eq!(pagination.current_page, 7);
+    assert_eq!(pagination.page_range(100), 28..32);
+}
+
+#[test]
+fn test_paging_stops_at_both_ends()
+{
+    let mut pagination = Pagination::new(10);
+
+    assert!(pagination.next_page(25));
+    assert!(pagination.next_page(25));
+    assert!(!pagination.next_page(25));
+    assert_eq!(pagination.current_page, 2);
+
+    assert!(pagination.previous_page());
+    assert!(pagination.previous_page());
+    assert!(!pagination.previous_page());
+    assert!(!pagination.next_page(0));
+}
@@ -0,0 +1,83 @@
+use ananicy_rule_o_matic::domain::models::{AttributeReading, CpuMax, Pressure};
+use ananicy_rule_o_matic::infrastructure::cgroup_reader::CgroupReader;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_read_cgroup_resources()
+{
+    let mount_dir = tempdir().unwrap();
+    let cgroup_dir = mount_dir.path().join("cpu50");
+    fs::create_dir_all(&cgroup_dir).unwrap();
+
+    fs::write(cgroup_dir.join("cpu.max"), "200000 100000\n").unwrap();
+    fs::write(cgroup_dir.join("cpu.weight"), "100\n").unwrap();
+    fs::write(cgroup_dir.join("memory.max"), "max\n").unwrap();
+    fs::write(cgroup_dir.join("io.weight"), "default 100\n").unwrap();
+    fs::write(
+        cgroup_dir.join("cpu.pressure"),
+        "some avg10=1.50 avg60=0.80 avg300=0.20 total=12345\nfull avg10=0.25 avg60=0.10 avg300=0.00 total=42\n",
+    )
+    .unwrap();
+
+    let cgroup_resources = CgroupReader::new(mount_dir.path().to_path_buf()).read("/cpu50", 4);
+
+    assert_eq!(cgroup_resources.path, "/cpu50");
+    assert_eq!(
+        cgroup_resources.cpu_max,
+        AttributeReading::Value(CpuMax {
+            quota: Some(200000),
+            period: 100000,
+        })
+    );
+    assert_eq!(cgroup_resources.cpu_weight, AttributeReading::Value(100));
+    assert_eq!(cgroup_resources.memory_max, AttributeReading::Value("max".to_string()));
+    assert_eq!(
+        cgroup_resources.io_weight,
+        AttributeReading::Value("default 100".to_string())
+    );
+    assert_eq!(
+        cgroup_resources.cpu_pressure,
+        AttributeReading::Value(Pressure {
+            some_avg10: 1.5,
+            full_avg10: Some(0.25),
+        })
+    );
+
+    // The io controller is not enabled for this cgroup.
+    assert_eq!(cgroup_resources.io_pressure, AttributeReading::Unsupported);
+
+    assert_eq!(cgroup_resources.cpu_quota_percent(), Some(50.0));
+    assert!(cgroup_resources.enforces_cpu_quota(50));
+    assert!(!cgroup_resources.enforces_cpu_quota(80));
+}
+
+#[test]
+fn test_unlimited_cpu_max_enforces_no_quota()
+{
+    let mount_dir = tempdir().unwrap();
+    fs::write(mount_dir.path().join("cpu.max"), "max 100000\n").unwrap();
+
+    let cgroup_resources = CgroupReader::new(mount_dir.path().to_path_buf()).read("/", 8);
+
+    assert_eq!(
+        cgroup_resources.cpu_max,
+        AttributeReading::Value(CpuMax {
+            quota: None,
+            period: 100000,
+        })
+    );
+    assert_eq!(cgroup_resources.cpu_quota_percent(), None);
+    assert!(!cgroup_resources.enforces_cpu_quota(80));
+}
+
+#[test]
+fn test_removed_cgroup_is_gone()
+{
+    let mount_dir = tempdir().unwrap();
+
+    let cgroup_resources = CgroupReader::new(mount_dir.path().to_path_buf()).read("/removed.scope", 4);
+
+    assert_eq!(cgroup_resources.cpu_max, AttributeReading::Gone);
+    assert_eq!(cgroup_resources.memory_pressure, AttributeReading::Gone);
+}
@@ -0,0 +1,89 @@
+use ananicy_rule_o_matic::domain::models::{AppConfig, MatchingMode, OutputFormat, Theme};
+use ananicy_rule_o_matic::infrastructure::config_repository::ConfigRepository;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_missing_default_config_uses_defaults()
+{
+    let dir = tempdir().unwrap();
+
+    let config = ConfigRepository::new_with_default_path(dir.path().join("config.toml"))
+        .load()
+        .unwrap();
+
+    assert_eq!(config, AppConfig::default());
+    assert_eq!(config.rule_roots, vec![PathBuf::from("/etc/ananicy.d")]);
+    assert_eq!(config.items_per_page, 50);
+}
+
+#[test]
+fn test_partial_config_keeps_other_defaults()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("config.toml");
+    fs::write(
+        &file_path,
+        r#"
+rule_roots = ["/etc/ananicy.d", "/home/user/ananicy.d"]
+output_format = "ndjson"
+theme = "light"
+tick_rate_ms = 250
+matching = "discovery"
+editor_line_arg = "-g {file}:{line}"
+"#,
+    )
+    .unwrap();
+
+    let config = ConfigRepository::new_with_path(file_path).load().unwrap();
+
+    assert_eq!(config.rule_roots.len(), 2);
+    assert_eq!(config.output_format, OutputFormat::Ndjson);
+    assert_eq!(config.theme, Theme::Light);
+    assert_eq!(config.tick_rate_ms, 250);
+    assert_eq!(config.matching, MatchingMode::Discovery);
+    assert_eq!(config.editor_line_arg.as_deref(), Some("-g {file}:{line}"));
+    assert_eq!(config.language, "en");
+    assert_eq!(config.custom_rules_dir, "99-custom");
+}
+
+#[test]
+fn test_invalid_config_is_reported()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("config.toml");
+
+    fs::write(&file_path, "items_per_pgae = 20\n").unwrap();
+    assert!(ConfigRepository::new_with_path(file_path.clone()).load().is_err());
+
+    fs::write(&file_path, "theme = \"solarized\"\n").unwrap();
+    assert!(ConfigRepository::new_with_path(file_path).load().is_err());
+}
+
+#[test]
+fn test_missing_explicit_config_is_an_error()
+{
+    let dir = tempdir().unwrap();
+
+    let error = ConfigRepository::new_with_path(dir.path().join("config.toml"))
+        .load()
+        .unwrap_err();
+
+    assert!(error.to_string().contains("does not exist"));
+}
+
+#[test]
+fn test_config_rejects_zero_tick_rate_and_page_size()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("config.toml");
+
+    for content in ["tick_rate_ms = 0", "tick_rate_ms = 99", "items_per_page = 0"]
+    {
+        fs::write(&file_path, content).unwrap();
+
+        let error = ConfigRepository::new_with_path(file_path.clone()).load().unwrap_err();
+        assert!(format!("{:#}", error).contains("must be at least"), "{}", content);
+    }
+}
@@ -0,0 +1,99 @@
+use ananicy_rule_o_matic::infrastructure::editor_launcher::EditorLauncher;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// A script that writes its arguments to the returned file.
+fn create_recording_editor(dir: &Path, name: &str) -> (PathBuf, PathBuf)
+{
+    let arguments_file = dir.join("arguments");
+    let editor_script = dir.join(name);
+
+    fs::write(
+        &editor_script,
+        format!("#!/bin/sh\necho \"$@\" > {}\n", arguments_file.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&editor_script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    (editor_script, arguments_file)
+}
+
+#[test]
+fn test_editor_receives_line_and_file()
+{
+    let dir = tempdir().unwrap();
+    let (editor_script, arguments_file) = create_recording_editor(dir.path(), "vim");
+
+    let rule_file = dir.path().join("games.rules");
+    let editor_launcher = EditorLauncher::new_with_command(format!("{} --wait", editor_script.display()), None);
+
+    editor_launcher.open(&rule_file, Some(7)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&arguments_file).unwrap(),
+        format!("--wait +7 {}\n", rule_file.display())
+    );
+}
+
+#[test]
+fn test_unknown_editor_only_receives_file()
+{
+    let dir = tempdir().unwrap();
+    let (editor_script, arguments_file) = create_recording_editor(dir.path(), "editor.sh");
+
+    let rule_file = dir.path().join("games.rules");
+    EditorLauncher::new_with_command(editor_script.display().to_string(), None)
+        .open(&rule_file, Some(7))
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&arguments_file).unwrap(),
+        format!("{}\n", rule_file.display())
+    );
+}
+
+#[test]
+fn test_line_argument_template_is_used()
+{
+    let dir = tempdir().unwrap();
+    let rule_file = dir.path().join("games.rules");
+
+    let (code_script, arguments_file) = create_recording_editor(dir.path(), "code");
+    EditorLauncher::new_with_command(code_script.display().to_string(), None)
+        .open(&rule_file, Some(7))
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&arguments_file).unwrap(),
+        format!("-g {}:7\n", rule_file.display())
+    );
+
+    let (editor_script, arguments_file) = create_recording_editor(dir.path(), "editor.sh");
+    EditorLauncher::new_with_command(editor_script.display().to_string(), Some("--line {line}".to_string()))
+        .open(&rule_file, Some(7))
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&arguments_file).unwrap(),
+        format!("--line 7 {}\n", rule_file.display())
+    );
+}
+
+#[test]
+fn test_failing_editor_is_reported()
+{
+    let dir = tempdir().unwrap();
+
+    assert!(
+        EditorLauncher::new_with_command("false".to_string(), None)
+            .open(&dir.path().join("games.rules"), None)
+            .is_err()
+    );
+    assert!(
+        EditorLauncher::new_with_command("   ".to_string(), None)
+            .open(&dir.path().join("games.rules"), None)
+            .is_err()
+    );
+}
@@ -1 +1,7 @@
+mod cgroup_reader_tests;
+mod config_repository_tests;
+mod editor_launcher_tests;
+mod proc_scanner_tests;
 mod rule_repository_tests;
+mod snapshot_repository_tests;
+mod system_probe_tests;
@@ -0,0 +1,191 @@
+use ananicy_rule_o_matic::infrastructure::proc_scanner::ProcScanner;
+use std::fs;
+use tempfile::{TempDir, tempdir};
+
+fn create_fake_process(proc_dir: &TempDir, process_id: i32, comm: &str, cmdline: &[&str])
+{
+    let process_dir = proc_dir.path().join(process_id.to_string());
+    fs::create_dir_all(&process_dir).unwrap();
+
+    fs::write(process_dir.join("comm"), format!("{}\n", comm)).unwrap();
+
+    let mut cmdline_content = cmdline.join("\0");
+    if !cmdline.is_empty()
+    {
+        cmdline_content.push('\0');
+    }
+    fs::write(process_dir.join("cmdline"), cmdline_content).unwrap();
+}
+
+fn write_fake_stat(proc_dir: &TempDir, process_id: i32, comm: &str, parent_process_id: i32, start_time: u64)
+{
+    // Fields 5 to 21 do not matter to the scanner.
+    let filler = vec!["0"; 17].join(" ");
+
+    fs::write(
+        proc_dir.path().join(format!("{}/stat", process_id)),
+        format!(
+            "{} ({}) S {} {} {}\n",
+            process_id, comm, parent_process_id, filler, start_time
+        ),
+    )
+    .unwrap();
+}
+
+fn remove_fake_process(proc_dir: &TempDir, process_id: i32)
+{
+    fs::remove_dir_all(proc_dir.path().join(process_id.to_string())).unwrap();
+}
+
+#[test]
+fn test_scan_reads_new_processes()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 1, "systemd", &["/sbin/init", "splash"]);
+    create_fake_process(&proc_dir, 42, "steam", &["/usr/bin/steam", "-silent"]);
+    fs::create_dir_all(proc_dir.path().join("sys")).unwrap();
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+    let delta = scanner.scan();
+
+    let added_pids: Vec<i32> = delta.added.iter().map(|(pid, _)| *pid).collect();
+    assert_eq!(added_pids, vec![1, 42]);
+    assert!(delta.removed.is_empty());
+    assert_eq!(scanner.entries().len(), 2);
+
+    let steam = scanner.get(42).expect("Process 42 not scanned");
+    assert_eq!(steam.name, "steam");
+    assert_eq!(steam.cmdline, vec!["/usr/bin/steam", "-silent"]);
+}
+
+#[test]
+fn test_scan_kernel_thread_has_empty_cmdline()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 2, "kthreadd", &[]);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+    scanner.scan();
+
+    let kthreadd = scanner.get(2).unwrap();
+    assert_eq!(kthreadd.name, "kthreadd");
+    assert!(kthreadd.cmdline.is_empty());
+}
+
+#[test]
+fn test_scan_drops_exited_processes()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 10, "make", &["make", "-j16"]);
+    create_fake_process(&proc_dir, 11, "cc1", &["cc1"]);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+    scanner.scan();
+
+    remove_fake_process(&proc_dir, 11);
+    let delta = scanner.scan();
+
+    assert!(delta.added.is_empty());
+    assert_eq!(delta.removed.len(), 1);
+    assert_eq!(delta.removed[0].0, 11);
+    assert_eq!(delta.removed[0].1.name, "cc1");
+    assert!(scanner.get(11).is_none());
+    assert!(scanner.get(10).is_some());
+}
+
+#[test]
+fn test_scan_is_incremental_for_settled_processes()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 10, "bash", &["bash"]);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+
+    for _ in 0..3
+    {
+        scanner.scan();
+    }
+
+    // A settled process is not re-read until the periodic resync.
+    create_fake_process(&proc_dir, 10, "renamed", &["renamed"]);
+    let delta = scanner.scan();
+
+    assert!(delta.is_empty());
+    assert_eq!(scanner.get(10).unwrap().name, "bash");
+}
+
+#[test]
+fn test_scan_detects_exec_of_young_process()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 20, "bash", &["bash", "-c", "make"]);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+    scanner.scan();
+
+    create_fake_process(&proc_dir, 20, "make", &["make"]);
+    let delta = scanner.scan();
+
+    assert_eq!(delta.added.len(), 1);
+    assert_eq!(delta.added[0].0, 20);
+    assert_eq!(delta.added[0].1.name, "make");
+    assert_eq!(delta.removed.len(), 1);
+    assert_eq!(delta.removed[0].1.name, "bash");
+    assert_eq!(scanner.get(20).unwrap().name, "make");
+}
+
+#[test]
+fn test_scan_missing_proc_directory()
+{
+    let mut scanner = ProcScanner::new_with_proc_path("/path/to/nowhere/hopefully".into());
+
+    let delta = scanner.scan();
+
+    assert!(delta.is_empty());
+    assert!(scanner.entries().is_empty());
+}
+
+#[test]
+fn test_scan_reads_parent_process_id()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 42, "Web Content", &["/usr/lib/firefox/firefox"]);
+    fs::write(
+        proc_dir.path().join("42/stat"),
+        "42 (Web (Content)) S 7 42 42 0 -1 4194560 0 0 0 0\n",
+    )
+    .unwrap();
+    create_fake_process(&proc_dir, 43, "orphan", &["orphan"]);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+    scanner.scan();
+
+    assert_eq!(scanner.get(42).unwrap().parent_process_id, 7);
+    assert_eq!(scanner.get(43).unwrap().parent_process_id, 0);
+}
+
+#[test]
+fn test_scan_rereads_reused_process_ids()
+{
+    let proc_dir = tempdir().unwrap();
+    create_fake_process(&proc_dir, 30, "steam", &["steam"]);
+    write_fake_stat(&proc_dir, 30, "steam", 1, 1000);
+
+    let mut scanner = ProcScanner::new_with_proc_path(proc_dir.path().to_path_buf());
+
+    for _ in 0..3
+    {
+        scanner.scan();
+    }
+
+    // The process exited between scans and its PID went to a new one.
+    create_fake_process(&proc_dir, 30, "bash", &["bash"]);
+    write_fake_stat(&proc_dir, 30, "bash", 1, 5000);
+    let delta = scanner.scan();
+
+    assert_eq!(delta.removed.len(), 1);
+    assert_eq!(delta.removed[0].1.name, "steam");
+    assert_eq!(delta.added.len(), 1);
+    assert_eq!(delta.added[0].1.name, "bash");
+    assert_eq!(scanner.get(30).unwrap().name, "bash");
+}
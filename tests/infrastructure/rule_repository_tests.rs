@@ -1,3 +1,4 @@
+use ananicy_rule_o_matic::domain::models::AnanicyRule;
 use ananicy_rule_o_matic::infrastructure::rule_repository::RuleRepository;
 use std::fs;
 use std::path::PathBuf;
@@ -141,7 +142,7 @@ fn test_invalid_json_lines_skipped_and_reported()
 {"name": "valid2"}
 not even json
 "#;
-    fs::write(file_path, content).unwrap();
+    fs::write(&file_path, content).unwrap();
 
     let rule_repository = RuleRepository::new_with_base_path(dir.path().to_path_buf());
 
@@ -150,11 +151,15 @@ not even json
     assert_eq!(rules.len(), 2);
     assert_eq!(rules[0].data.name.as_deref(), Some("valid1"));
     assert_eq!(rules[1].data.name.as_deref(), Some("valid2"));
+    assert_eq!(rules[0].line_number, 2);
+    assert_eq!(rules[1].line_number, 4);
 
     assert_eq!(errors.len(), 2);
-    assert!(errors[0].contains("line 3"));
-    assert!(errors[1].contains("line 5"));
-    assert!(errors[1].contains("start with '{'"));
+    assert!(errors[0].message.contains("line 3"));
+    assert!(errors[1].message.contains("line 5"));
+    assert!(errors[1].message.contains("start with '{'"));
+    assert_eq!(errors[1].file.as_ref(), Some(&file_path));
+    assert_eq!(errors[1].line, Some(5));
 }
 
 #[test]
@@ -167,7 +172,7 @@ fn test_non_existent_directory()
 
     assert!(rules.is_empty());
     assert_eq!(errors.len(), 1);
-    assert!(errors[0].contains("does not exist"));
+    assert!(errors[0].message.contains("does not exist"));
 }
 
 #[test]
@@ -181,3 +186,92 @@ fn test_empty_directory()
     assert!(rules.is_empty());
     assert!(errors.is_empty());
 }
+
+#[test]
+fn test_load_cgroup_definitions()
+{
+    let dir = tempdir().unwrap();
+    let content = "# CPU limited group\n{\"cgroup\": \"cpu80\", \"CPUQuota\": 80}\n\n{ invalid }\n";
+
+    fs::write(dir.path().join("00-cgroups.cgroups"), content).unwrap();
+    fs::write(
+        dir.path().join("test.rules"),
+        r#"{"name": "test_process", "cgroup": "cpu80"}"#,
+    )
+    .unwrap();
+
+    let rule_repository = RuleRepository::new_with_base_path(dir.path().to_path_buf());
+
+    let (definitions, errors) = rule_repository.load_cgroup_definitions().unwrap();
+
+    assert_eq!(definitions.len(), 1);
+    assert_eq!(definitions[0].name.as_deref(), Some("cpu80"));
+    assert_eq!(definitions[0].cpu_quota, Some(80));
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("Parse error"));
+    assert!(errors[0].message.contains("line 4"));
+}
+
+#[test]
+fn test_multiple_rule_roots()
+{
+    let system_dir = tempdir().unwrap();
+    let user_dir = tempdir().unwrap();
+
+    fs::create_dir_all(system_dir.path().join("00-default")).unwrap();
+    fs::write(
+        system_dir.path().join("00-default/games.rules"),
+        r#"{"name": "steam", "nice": -1}"#,
+    )
+    .unwrap();
+    fs::write(user_dir.path().join("mine.rules"), r#"{"name": "steam", "nice": -5}"#).unwrap();
+
+    let rule_repository = RuleRepository::new_with_base_paths(
+        vec![system_dir.path().to_path_buf(), user_dir.path().to_path_buf()],
+        "50-mine".to_string(),
+    );
+
+    let (rules, errors) = rule_repository.load_all().unwrap();
+
+    assert!(errors.is_empty());
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[1].source_file, user_dir.path().join("mine.rules"));
+
+    let rule = AnanicyRule {
+        name: Some("game".to_string()),
+        ..Default::default()
+    };
+    let file_path = rule_repository.save_rule(&rule).unwrap();
+
+    assert_eq!(file_path, user_dir.path().join("50-mine/game.rules"));
+    assert_eq!(
+        rule_repository.resolve_rule_file(&PathBuf::from("x/y.rules")),
+        user_dir.path().join("x/y.rules")
+    );
+}
+
+#[test]
+fn test_apply_change_refuses_files_changed_since_planning()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("test.rules");
+    fs::write(&file_path, "{\"name\": \"first\"}\n").unwrap();
+
+    let rule_repository = RuleRepository::new_with_base_path(dir.path().to_path_buf());
+
+    let rule = AnanicyRule {
+        name: Some("second".to_string()),
+        ..Default::default()
+    };
+
+    let change = rule_repository.plan_append_rule(&file_path, &rule).unwrap();
+    assert_eq!(change.old_content, "{\"name\": \"first\"}\n");
+    assert_eq!(change.new_content, "{\"name\": \"first\"}\n{\"name\":\"second\"}\n");
+    assert!(change.unified_diff().contains("+{\"name\":\"second\"}"));
+
+    fs::write(&file_path, "{\"name\": \"edited\"}\n").unwrap();
+
+    assert!(rule_repository.apply_change(&change).is_err());
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "{\"name\": \"edited\"}\n");
+}
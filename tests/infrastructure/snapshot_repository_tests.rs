@@ -0,0 +1,141 @@
+use ananicy_rule_o_matic::domain::models::{AttributeReading, ProcessInfo, ProcessRecord, ProcessSnapshot};
+use ananicy_rule_o_matic::infrastructure::snapshot_repository::SnapshotRepository;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+fn create_snapshot() -> ProcessSnapshot
+{
+    ProcessSnapshot {
+        captured_at: "2026-01-01T12:00:00+00:00".to_string(),
+        hostname: Some("testhost".to_string()),
+        kernel_version: Some("6.12.0".to_string()),
+        capabilities: None,
+        processes: vec![ProcessRecord {
+            info: ProcessInfo {
+                process_id: 42,
+                name: "steam".to_string(),
+                nice: AttributeReading::Value(-5),
+                oom_score_adj: AttributeReading::Value(0),
+                cgroup: AttributeReading::Value("/user.slice".to_string()),
+                sched_policy: AttributeReading::Value("normal".to_string()),
+                rtprio: AttributeReading::Value(0),
+                ioclass: AttributeReading::Value("best-effort".to_string()),
+                latency_nice: AttributeReading::Unsupported,
+                autogroup: AttributeReading::Unsupported,
+            },
+            cmdline: vec!["/usr/bin/steam".to_string(), "-silent".to_string()],
+            exe: Some(PathBuf::from("/usr/lib/steam/steam")),
+            uid: Some(1000),
+            parent_process_id: Some(1),
+        }],
+    }
+}
+
+#[test]
+fn test_snapshot_roundtrip()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("snapshot.json");
+
+    let snapshot_repository = SnapshotRepository::new(file_path.clone());
+    snapshot_repository.save(&create_snapshot()).unwrap();
+
+    assert!(file_path.exists());
+
+    let loaded = snapshot_repository.load().unwrap();
+
+    assert_eq!(loaded.captured_at, "2026-01-01T12:00:00+00:00");
+    assert_eq!(loaded.hostname.as_deref(), Some("testhost"));
+    assert_eq!(loaded.processes.len(), 1);
+
+    let record = &loaded.processes[0];
+
+    assert_eq!(record.info.process_id, 42);
+    assert_eq!(record.info.name, "steam");
+    assert_eq!(record.info.nice, AttributeReading::Value(-5));
+    assert_eq!(record.info.latency_nice, AttributeReading::Unsupported);
+    assert_eq!(record.cmdline, vec!["/usr/bin/steam", "-silent"]);
+    assert_eq!(record.exe, Some(PathBuf::from("/usr/lib/steam/steam")));
+    assert_eq!(record.uid, Some(1000));
+}
+
+#[test]
+fn test_snapshot_process_fields_are_flattened()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("snapshot.json");
+
+    SnapshotRepository::new(file_path.clone())
+        .save(&create_snapshot())
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(file_path).unwrap()).unwrap();
+    let process = &json["processes"][0];
+
+    assert_eq!(process["process_id"], 42);
+    assert_eq!(process["name"], "steam");
+    assert_eq!(process["uid"], 1000);
+    assert_eq!(process["nice"]["value"], -5);
+    assert_eq!(process["latency_nice"], "unsupported");
+}
+
+#[test]
+fn test_load_snapshot_without_capabilities()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("snapshot.json");
+    fs::write(
+        &file_path,
+        r#"{ "captured_at": "2026-01-01T12:00:00+00:00", "hostname": null, "kernel_version": null, "processes": [] }"#,
+    )
+    .unwrap();
+
+    let loaded = SnapshotRepository::new(file_path).load().unwrap();
+
+    assert!(loaded.capabilities.is_none());
+}
+
+#[test]
+fn test_load_process_without_autogroup()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("snapshot.json");
+
+    let mut json: serde_json::Value = serde_json::to_value(create_snapshot()).unwrap();
+    json["processes"][0].as_object_mut().unwrap().remove("autogroup");
+    fs::write(&file_path, json.to_string()).unwrap();
+
+    let loaded = SnapshotRepository::new(file_path).load().unwrap();
+
+    assert_eq!(loaded.processes[0].info.autogroup, AttributeReading::Unsupported);
+}
+
+#[test]
+fn test_load_missing_snapshot()
+{
+    let snapshot_repository = SnapshotRepository::new(PathBuf::from("/path/to/nowhere/snapshot.json"));
+
+    let result = snapshot_repository.load();
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Failed to read snapshot file"));
+}
+
+#[test]
+fn test_load_invalid_snapshot()
+{
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("snapshot.json");
+    fs::write(&file_path, "{ not a snapshot }").unwrap();
+
+    let result = SnapshotRepository::new(file_path).load();
+
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Failed to parse snapshot file")
+    );
+}
@@ -0,0 +1,124 @@
+use ananicy_rule_o_matic::domain::models::{BlockDeviceScheduler, SystemCapabilities};
+use ananicy_rule_o_matic::infrastructure::system_probe::SystemProbe;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::{TempDir, tempdir};
+
+fn write_file(root: &Path, relative_path: &str, content: &str)
+{
+    let path = root.join(relative_path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn create_fake_root() -> TempDir
+{
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(
+        root,
+        "proc/mounts",
+        "proc /proc proc rw,nosuid 0 0\ncgroup2 /sys/fs/cgroup cgroup2 rw,nosuid 0 0\n",
+    );
+    write_file(root, "sys/fs/cgroup/cgroup.controllers", "cpuset cpu io memory pids\n");
+    write_file(root, "sys/block/sda/queue/scheduler", "mq-deadline kyber [bfq] none\n");
+    write_file(
+        root,
+        "sys/block/nvme0n1/queue/scheduler",
+        "[none] mq-deadline kyber bfq\n",
+    );
+    write_file(root, "sys/block/loop0/queue/scheduler", "[none] mq-deadline\n");
+    write_file(root, "proc/sys/kernel/sched_autogroup_enabled", "1\n");
+    write_file(root, "usr/bin/ananicy-cpp", "");
+    write_file(root, "usr/lib/systemd/system/ananicy-cpp.service", "[Service]\n");
+
+    dir
+}
+
+#[test]
+fn test_probe_fake_root()
+{
+    let dir = create_fake_root();
+
+    let capabilities = SystemProbe::new_with_root_path(dir.path().to_path_buf()).probe(true);
+
+    assert!(capabilities.latency_nice);
+    assert_eq!(capabilities.cgroup2_mount, Some(PathBuf::from("/sys/fs/cgroup")));
+    assert_eq!(
+        capabilities.cgroup2_controllers,
+        vec!["cpuset", "cpu", "io", "memory", "pids"]
+    );
+    assert_eq!(
+        capabilities.block_devices,
+        vec![
+            BlockDeviceScheduler {
+                device: "nvme0n1".to_string(),
+                scheduler: "none".to_string(),
+            },
+            BlockDeviceScheduler {
+                device: "sda".to_string(),
+                scheduler: "bfq".to_string(),
+            },
+        ]
+    );
+    assert_eq!(capabilities.autogroup_enabled, Some(true));
+    assert_eq!(capabilities.ananicy_binary, Some(PathBuf::from("/usr/bin/ananicy-cpp")));
+    assert_eq!(
+        capabilities.ananicy_service_unit,
+        Some(PathBuf::from("/usr/lib/systemd/system/ananicy-cpp.service"))
+    );
+
+    assert!(capabilities.supports_cgroup_cpu());
+    assert!(capabilities.supports_ioclass());
+}
+
+#[test]
+fn test_probe_empty_root()
+{
+    let dir = tempdir().unwrap();
+
+    let capabilities = SystemProbe::new_with_root_path(dir.path().to_path_buf()).probe(false);
+
+    assert!(!capabilities.latency_nice);
+    assert!(capabilities.cgroup2_mount.is_none());
+    assert!(capabilities.cgroup2_controllers.is_empty());
+    assert!(capabilities.block_devices.is_empty());
+    assert!(capabilities.autogroup_enabled.is_none());
+    assert!(capabilities.ananicy_binary.is_none());
+    assert!(capabilities.ananicy_service_unit.is_none());
+
+    assert!(!capabilities.supports_cgroup_cpu());
+}
+
+#[test]
+fn test_cgroup_without_cpu_controller()
+{
+    let capabilities = SystemCapabilities {
+        cgroup2_mount: Some(PathBuf::from("/sys/fs/cgroup")),
+        cgroup2_controllers: vec!["memory".to_string(), "pids".to_string()],
+        ..Default::default()
+    };
+
+    assert!(!capabilities.supports_cgroup_cpu());
+}
+
+#[test]
+fn test_ioclass_unsupported_without_priority_scheduler()
+{
+    let capabilities = SystemCapabilities {
+        block_devices: vec![
+            BlockDeviceScheduler {
+                device: "nvme0n1".to_string(),
+                scheduler: "none".to_string(),
+            },
+            BlockDeviceScheduler {
+                device: "sda".to_string(),
+                scheduler: "kyber".to_string(),
+            },
+        ],
+        ..Default::default()
+    };
+
+    assert!(!capabilities.supports_ioclass());
+}